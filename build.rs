@@ -0,0 +1,43 @@
+//! Rebuild the game when a folder that an `#[asset(glob = "...")]` reads gains
+//! or loses files, since the derive lists the folder when it expands.
+
+use std::{fs, path::Path};
+
+fn main() {
+    println!("cargo:rerun-if-changed=src");
+    let mut folders = Vec::new();
+    find_glob_folders(Path::new("src"), &mut folders);
+    folders.sort();
+    folders.dedup();
+    for folder in folders {
+        println!("cargo:rerun-if-changed={folder}");
+    }
+}
+
+/// Collect the folders of every `glob = "folder/pattern"` in the sources under
+/// `path`.
+fn find_glob_folders(path: &Path, folders: &mut Vec<String>) {
+    let Ok(entries) = fs::read_dir(path) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            find_glob_folders(&path, folders);
+            continue;
+        }
+        if path.extension().is_none_or(|extension| extension != "rs") {
+            continue;
+        }
+        let Ok(source) = fs::read_to_string(&path) else {
+            continue;
+        };
+        for (index, key) in source.match_indices("glob = \"") {
+            let Some((glob, _)) = source[index + key.len()..].split_once('"') else {
+                continue;
+            };
+            let (folder, _) = glob.rsplit_once('/').unwrap_or(("", glob));
+            folders.push(Path::new("assets").join(folder).display().to_string());
+        }
+    }
+}
@@ -0,0 +1,247 @@
+//! Derive macros for the game.
+
+use std::{env, fs, path::Path};
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{Data, DeriveInput, Expr, Fields, LitStr, parse_macro_input};
+
+/// Derive `FromWorld` for a collection of asset handles, loading each field as
+/// described by its `#[asset(...)]` attribute:
+///
+/// - `path = "images/ducky.png"` loads a single asset.
+/// - `glob = "audio/sound_effects/step*.ogg"` loads every file in a folder whose
+///   name matches, in alphabetical order, into a `Vec` of handles. `*` and `?`
+///   are supported in the file name. The folder is read when the game is
+///   compiled, so this works on the web too. The game's build script tells
+///   Cargo to watch globbed folders, so adding or removing a matching file
+///   rebuilds the game with it.
+/// - `sampler = "nearest"` or `sampler = "linear"` sets how images are sampled,
+///   along with `path` or `glob`.
+/// - `texture_atlas(tile_size = 32, columns = 6, rows = 2, padding = 1)` adds a
+///   grid `TextureAtlasLayout` of square tiles. The settings can be any `u32`
+///   expressions, and `padding` is optional.
+///
+/// Fields without an attribute are set to their default.
+#[proc_macro_derive(AssetCollection, attributes(asset))]
+pub fn derive_asset_collection(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    asset_collection(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+fn asset_collection(input: DeriveInput) -> syn::Result<TokenStream2> {
+    let Data::Struct(data) = &input.data else {
+        return Err(syn::Error::new_spanned(
+            &input.ident,
+            "asset collections must be structs",
+        ));
+    };
+    let Fields::Named(fields) = &data.fields else {
+        return Err(syn::Error::new_spanned(
+            &input.ident,
+            "asset collections must have named fields",
+        ));
+    };
+
+    let mut layouts = Vec::new();
+    let mut values = Vec::new();
+    let mut loads_assets = false;
+    for field in &fields.named {
+        let ident = field.ident.as_ref().unwrap();
+        let asset = FieldAsset::parse(field)?;
+        loads_assets |= matches!(asset.source, Source::Path(_) | Source::Glob(_));
+        let value = match asset.source {
+            Source::None => quote!(::core::default::Default::default()),
+            Source::Path(path) => load(&path, asset.sampler.as_ref()),
+            Source::Glob(glob) => {
+                let loads = expand_glob(&glob)?
+                    .into_iter()
+                    .map(|path| load(&LitStr::new(&path, glob.span()), asset.sampler.as_ref()));
+                quote!(::std::vec![#(#loads),*])
+            }
+            Source::TextureAtlas {
+                tile_size,
+                columns,
+                rows,
+                padding,
+            } => {
+                let padding = match padding {
+                    Some(padding) => quote!(::core::option::Option::Some(
+                        ::bevy::math::UVec2::splat(#padding)
+                    )),
+                    None => quote!(::core::option::Option::None),
+                };
+                layouts.push(quote! {
+                    let #ident = world
+                        .resource_mut::<::bevy::asset::Assets<::bevy::image::TextureAtlasLayout>>()
+                        .add(::bevy::image::TextureAtlasLayout::from_grid(
+                            ::bevy::math::UVec2::splat(#tile_size),
+                            #columns,
+                            #rows,
+                            #padding,
+                            ::core::option::Option::None,
+                        ));
+                });
+                quote!(#ident)
+            }
+        };
+        values.push(quote!(#ident: #value));
+    }
+
+    let asset_server =
+        loads_assets.then(|| quote!(let assets = world.resource::<::bevy::asset::AssetServer>();));
+    let name = &input.ident;
+    let (impl_generics, type_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::bevy::ecs::world::FromWorld for #name #type_generics #where_clause {
+            fn from_world(world: &mut ::bevy::ecs::world::World) -> Self {
+                #(#layouts)*
+                #asset_server
+                Self {
+                    #(#values,)*
+                }
+            }
+        }
+    })
+}
+
+/// How to load a field, from its `#[asset(...)]` attribute.
+struct FieldAsset {
+    source: Source,
+    sampler: Option<TokenStream2>,
+}
+
+enum Source {
+    None,
+    Path(LitStr),
+    Glob(LitStr),
+    TextureAtlas {
+        tile_size: TokenStream2,
+        columns: TokenStream2,
+        rows: TokenStream2,
+        padding: Option<TokenStream2>,
+    },
+}
+
+impl FieldAsset {
+    fn parse(field: &syn::Field) -> syn::Result<Self> {
+        let mut asset = Self {
+            source: Source::None,
+            sampler: None,
+        };
+        for attribute in field
+            .attrs
+            .iter()
+            .filter(|attr| attr.path().is_ident("asset"))
+        {
+            attribute.parse_nested_meta(|meta| {
+                if meta.path.is_ident("path") {
+                    asset.source = Source::Path(meta.value()?.parse()?);
+                } else if meta.path.is_ident("glob") {
+                    asset.source = Source::Glob(meta.value()?.parse()?);
+                } else if meta.path.is_ident("sampler") {
+                    let sampler: LitStr = meta.value()?.parse()?;
+                    asset.sampler = Some(match sampler.value().as_str() {
+                        "nearest" => quote!(::bevy::image::ImageSampler::nearest()),
+                        "linear" => quote!(::bevy::image::ImageSampler::linear()),
+                        _ => {
+                            return Err(
+                                meta.error("expected `sampler = \"nearest\"` or `\"linear\"`")
+                            );
+                        }
+                    });
+                } else if meta.path.is_ident("texture_atlas") {
+                    let (mut tile_size, mut columns, mut rows, mut padding) =
+                        (None, None, None, None);
+                    meta.parse_nested_meta(|meta| {
+                        let value: Expr = meta.value()?.parse()?;
+                        let value = Some(quote!(#value));
+                        if meta.path.is_ident("tile_size") {
+                            tile_size = value;
+                        } else if meta.path.is_ident("columns") {
+                            columns = value;
+                        } else if meta.path.is_ident("rows") {
+                            rows = value;
+                        } else if meta.path.is_ident("padding") {
+                            padding = value;
+                        } else {
+                            return Err(meta.error("unknown texture atlas setting"));
+                        }
+                        Ok(())
+                    })?;
+                    let missing = |name| meta.error(format!("texture atlas is missing `{name}`"));
+                    asset.source = Source::TextureAtlas {
+                        tile_size: tile_size.ok_or_else(|| missing("tile_size"))?,
+                        columns: columns.ok_or_else(|| missing("columns"))?,
+                        rows: rows.ok_or_else(|| missing("rows"))?,
+                        padding,
+                    };
+                } else {
+                    return Err(meta.error("unknown asset setting"));
+                }
+                Ok(())
+            })?;
+        }
+        Ok(asset)
+    }
+}
+
+fn load(path: &LitStr, sampler: Option<&TokenStream2>) -> TokenStream2 {
+    match sampler {
+        Some(sampler) => quote! {
+            assets.load_with_settings(
+                #path,
+                |settings: &mut ::bevy::image::ImageLoaderSettings| {
+                    settings.sampler = #sampler;
+                },
+            )
+        },
+        None => quote!(assets.load(#path)),
+    }
+}
+
+/// The asset paths matching `glob`, read from the `assets` folder.
+fn expand_glob(glob: &LitStr) -> syn::Result<Vec<String>> {
+    let pattern = glob.value();
+    let (folder, file_pattern) = pattern.rsplit_once('/').unwrap_or(("", &pattern));
+    let manifest_dir = env::var("CARGO_MANIFEST_DIR").unwrap_or_default();
+    let folder_path = Path::new(&manifest_dir).join("assets").join(folder);
+    let entries = fs::read_dir(&folder_path).map_err(|error| {
+        syn::Error::new_spanned(
+            glob,
+            format!("can't read {}: {error}", folder_path.display()),
+        )
+    })?;
+
+    let mut paths: Vec<String> = entries
+        .filter_map(|entry| entry.ok()?.file_name().into_string().ok())
+        .filter(|name| glob_matches(file_pattern.as_bytes(), name.as_bytes()))
+        .map(|name| match folder {
+            "" => name,
+            folder => format!("{folder}/{name}"),
+        })
+        .collect();
+    if paths.is_empty() {
+        return Err(syn::Error::new_spanned(glob, "no assets match this glob"));
+    }
+    paths.sort();
+    Ok(paths)
+}
+
+/// Whether `name` matches `pattern`, where `*` matches any run of characters and
+/// `?` matches any single one.
+fn glob_matches(pattern: &[u8], name: &[u8]) -> bool {
+    match (pattern.first(), name.first()) {
+        (None, None) => true,
+        (Some(b'*'), _) => {
+            glob_matches(&pattern[1..], name)
+                || (!name.is_empty() && glob_matches(pattern, &name[1..]))
+        }
+        (Some(b'?'), Some(_)) => glob_matches(&pattern[1..], &name[1..]),
+        (Some(a), Some(b)) if a == b => glob_matches(&pattern[1..], &name[1..]),
+        _ => false,
+    }
+}
@@ -0,0 +1,110 @@
+"""Synthesize the intense music loop in `assets/audio/music`.
+
+Run from the repository root: `python3 raw_assets/intense_music.py`.
+Only uses the standard library, so the output is reproducible anywhere.
+
+The loop is eight bars of driving minor-key chiptune at 150 BPM. Every note
+ends before the loop does, so it repeats without a seam.
+"""
+
+import math
+import random
+import struct
+import wave
+
+RATE = 22050
+OUT = "assets/audio/music"
+BPM = 150
+BARS = 8
+STEP = 60 / BPM / 4  # A sixteenth note, in seconds.
+STEPS = BARS * 16
+
+# A minor: Am, F, C, G, twice, as MIDI root notes for the bass.
+CHORDS = [45, 41, 48, 43] * 2
+# The lead line, one entry per eighth note. None is a rest.
+LEAD = [
+    69, None, 72, 74, 76, None, 74, 72,
+    69, None, 72, 74, 77, 76, 74, None,
+    72, None, 76, 79, 81, None, 79, 76,
+    74, None, 71, 74, 79, 76, 74, None,
+    69, None, 72, 74, 76, None, 74, 72,
+    69, None, 72, 74, 77, 76, 74, None,
+    72, 74, 76, 79, 81, 79, 76, 74,
+    76, None, 74, None, 71, None, 69, None,
+]
+
+
+def frequency(note):
+    return 440 * 2 ** ((note - 69) / 12)
+
+
+def square(phase, duty=0.5):
+    return 1.0 if phase % 1.0 < duty else -1.0
+
+
+def add(buffer, start, samples):
+    for i, s in enumerate(samples):
+        if start + i < len(buffer):
+            buffer[start + i] += s
+
+
+def tone(note, seconds, duty, decay):
+    n = int(RATE * seconds)
+    f = frequency(note)
+    return [
+        square(f * i / RATE, duty) * math.exp(-i / (RATE * decay)) * min(1, (n - i) / 64)
+        for i in range(n)
+    ]
+
+
+def kick():
+    n = int(RATE * 0.15)
+    phase, out = 0.0, []
+    for i in range(n):
+        t = i / n
+        phase += 2 * math.pi * (120 - 80 * t) / RATE
+        out.append(math.sin(phase) * (1 - t) ** 2)
+    return out
+
+
+def noise(rng, seconds, decay):
+    n = int(RATE * seconds)
+    return [rng.uniform(-1, 1) * math.exp(-i / (RATE * decay)) for i in range(n)]
+
+
+def intense(rng):
+    buffer = [0.0] * int(RATE * STEP * STEPS)
+    step_samples = RATE * STEP
+    for step in range(STEPS):
+        start = int(step * step_samples)
+        beat = step % 16
+        # Four on the floor, a snare on two and four and a hi-hat on every offbeat.
+        if beat % 4 == 0:
+            add(buffer, start, [s * 0.9 for s in kick()])
+        if beat in (4, 12):
+            add(buffer, start, [s * 0.35 for s in noise(rng, 0.12, 0.04)])
+        if beat % 2 == 1:
+            add(buffer, start, [s * 0.12 for s in noise(rng, 0.03, 0.008)])
+        # Octave-jumping sixteenth-note bass.
+        root = CHORDS[step // 16]
+        bass = root + (12 if step % 2 else 0)
+        add(buffer, start, [s * 0.22 for s in tone(bass, STEP * 0.9, 0.5, 0.08)])
+        # The lead plays on every eighth note.
+        if step % 2 == 0 and (note := LEAD[step // 2]) is not None:
+            add(buffer, start, [s * 0.16 for s in tone(note, STEP * 1.8, 0.25, 0.25)])
+    return buffer
+
+
+def write(name, samples):
+    with wave.open(f"{OUT}/{name}.wav", "wb") as file:
+        file.setnchannels(1)
+        file.setsampwidth(2)
+        file.setframerate(RATE)
+        peak = max(abs(s) for s in samples) or 1.0
+        file.writeframes(
+            b"".join(struct.pack("<h", int(s / peak * 0.9 * 32767)) for s in samples)
+        )
+
+
+if __name__ == "__main__":
+    write("intense", intense(random.Random(7)))
@@ -0,0 +1,69 @@
+"""Synthesize the small gameplay sound effects in `assets/audio/sound_effects`.
+
+Run from the repository root: `python3 raw_assets/sound_effects.py`.
+Only uses the standard library, so the output is reproducible anywhere.
+"""
+
+import math
+import random
+import struct
+import wave
+
+RATE = 22050
+OUT = "assets/audio/sound_effects"
+
+
+def write(name, samples):
+    with wave.open(f"{OUT}/{name}.wav", "wb") as file:
+        file.setnchannels(1)
+        file.setsampwidth(2)
+        file.setframerate(RATE)
+        peak = max(abs(s) for s in samples) or 1.0
+        file.writeframes(
+            b"".join(struct.pack("<h", int(s / peak * 0.9 * 32767)) for s in samples)
+        )
+
+
+def lowpass(samples, amount):
+    out, last = [], 0.0
+    for s in samples:
+        last += (s - last) * amount
+        out.append(last)
+    return out
+
+
+def gunshot(rng):
+    n = int(RATE * 0.25)
+    noise = lowpass([rng.uniform(-1, 1) for _ in range(n)], 0.35)
+    return [
+        noise[i] * math.exp(-i / (RATE * 0.04))
+        + 0.8 * math.sin(2 * math.pi * 110 * i / RATE) * math.exp(-i / (RATE * 0.06))
+        for i in range(n)
+    ]
+
+
+def hit(rng):
+    n = int(RATE * 0.1)
+    noise = lowpass([rng.uniform(-1, 1) for _ in range(n)], 0.2)
+    return [
+        (noise[i] + 0.6 * math.sin(2 * math.pi * 320 * i / RATE))
+        * math.exp(-i / (RATE * 0.02))
+        for i in range(n)
+    ]
+
+
+def pop(_rng):
+    n = int(RATE * 0.18)
+    phase, out = 0.0, []
+    for i in range(n):
+        t = i / n
+        phase += 2 * math.pi * (900 - 700 * t) / RATE
+        out.append(math.sin(phase) * (1 - t) ** 2)
+    return out
+
+
+if __name__ == "__main__":
+    rng = random.Random(7)
+    write("gunshot", gunshot(rng))
+    write("hit", hit(rng))
+    write("pop", pop(rng))
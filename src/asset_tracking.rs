@@ -1,69 +1,337 @@
 //! A high-level way to load collections of asset handles as resources.
+//!
+//! Collections are declared with [`AssetCollection`], which describes how to
+//! load each field, and registered with [`LoadResource::load_resource`] as part
+//! of an [`AssetGroup`]. Nothing is loaded until its group is requested with
+//! [`LoadAssetGroup`], so each screen only waits on the assets it needs, and a
+//! group that is no longer needed can be freed again with [`UnloadAssetGroup`].
 
-use std::collections::VecDeque;
+use std::{collections::VecDeque, sync::Arc};
 
-use bevy::prelude::*;
+use bevy::{
+    asset::{LoadState, RecursiveDependencyLoadState, UntypedAssetId},
+    prelude::*,
+};
+pub use bevyjam7_macros::AssetCollection;
 
 pub(super) fn plugin(app: &mut App) {
     app.init_resource::<ResourceHandles>();
     app.add_systems(PreUpdate, load_resource_assets);
 }
 
+/// The screen or phase of the game a set of assets is needed for.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug, Reflect)]
+pub enum AssetGroup {
+    /// Assets used by the menus, from the title screen on.
+    Title,
+    /// Assets used by the intro cutscene before a new run.
+    Intro,
+    /// Assets used during a run.
+    Gameplay,
+    /// Assets only used by the credits.
+    Credits,
+}
+
 pub trait LoadResource {
-    /// This will load the [`Resource`] as an [`Asset`]. When all of its asset dependencies
-    /// have been loaded, it will be inserted as a resource. This ensures that the resource only
-    /// exists when the assets are ready.
-    fn load_resource<T: Resource + Asset + Clone + FromWorld>(&mut self) -> &mut Self;
+    /// This will load the [`Resource`] as an [`Asset`] once `group` is requested. When all of
+    /// its asset dependencies have been loaded, it will be inserted as a resource. This ensures
+    /// that the resource only exists when the assets are ready.
+    fn load_resource<T: Resource + Asset + Clone + FromWorld>(
+        &mut self,
+        group: AssetGroup,
+    ) -> &mut Self;
+
+    /// Like [`Self::load_resource`], but the resource itself is loaded from the asset file at
+    /// `path`, rather than built with [`FromWorld`].
+    fn load_resource_from_path<T: Resource + Asset + Clone>(
+        &mut self,
+        path: &'static str,
+        group: AssetGroup,
+    ) -> &mut Self;
 }
 
 impl LoadResource for App {
-    fn load_resource<T: Resource + Asset + Clone + FromWorld>(&mut self) -> &mut Self {
+    fn load_resource<T: Resource + Asset + Clone + FromWorld>(
+        &mut self,
+        group: AssetGroup,
+    ) -> &mut Self {
+        self.init_asset::<T>();
+        register::<T>(
+            self,
+            group,
+            Arc::new(|world| {
+                let value = T::from_world(world);
+                let mut dependencies = Vec::new();
+                value.visit_dependencies(&mut |id| dependencies.push(id));
+                let handle = world.resource::<AssetServer>().add(value);
+                (handle.untyped(), dependencies)
+            }),
+        )
+    }
+
+    fn load_resource_from_path<T: Resource + Asset + Clone>(
+        &mut self,
+        path: &'static str,
+        group: AssetGroup,
+    ) -> &mut Self {
         self.init_asset::<T>();
-        let world = self.world_mut();
-        let value = T::from_world(world);
-        let assets = world.resource::<AssetServer>();
-        let handle = assets.add(value);
+        register::<T>(
+            self,
+            group,
+            Arc::new(move |world| {
+                let handle = world.resource::<AssetServer>().load::<T>(path);
+                let id = handle.id().untyped();
+                (handle.untyped(), vec![id])
+            }),
+        )
+    }
+}
+
+fn register<T: Resource + Asset + Clone>(
+    app: &mut App,
+    group: AssetGroup,
+    load: LoadHandle,
+) -> &mut App {
+    let mut handles = app.world_mut().resource_mut::<ResourceHandles>();
+    handles.registered.push(RegisteredResource {
+        group,
+        load,
+        insert: |world, handle| {
+            let assets = world.resource::<Assets<T>>();
+            if let Some(value) = assets.get(handle.id().typed::<T>()) {
+                world.insert_resource(value.clone());
+            }
+        },
+        remove: |world| {
+            world.remove_resource::<T>();
+        },
+    });
+    app
+}
+
+/// A command that starts loading the resources of an [`AssetGroup`], unless
+/// they are already loading or loaded.
+pub struct LoadAssetGroup(pub AssetGroup);
+
+impl Command for LoadAssetGroup {
+    fn apply(self, world: &mut World) {
         let mut handles = world.resource_mut::<ResourceHandles>();
-        handles
-            .waiting
-            .push_back((handle.untyped(), |world, handle| {
-                let assets = world.resource::<Assets<T>>();
-                if let Some(value) = assets.get(handle.id().typed::<T>()) {
-                    world.insert_resource(value.clone());
-                }
-            }));
-        self
+        if handles.requested.contains(&self.0) {
+            return;
+        }
+        handles.requested.push(self.0);
+        let registered: Vec<(LoadHandle, InsertLoadedResource)> = handles
+            .registered
+            .iter()
+            .filter(|registered| registered.group == self.0)
+            .map(|registered| (registered.load.clone(), registered.insert))
+            .collect();
+        for (load, insert) in registered {
+            let (handle, dependencies) = load(world);
+            world
+                .resource_mut::<ResourceHandles>()
+                .waiting
+                .push_back(WaitingResource {
+                    group: self.0,
+                    handle,
+                    dependencies,
+                    loaded: 0,
+                    insert,
+                });
+        }
+    }
+}
+
+/// A command that removes the resources of an [`AssetGroup`] and drops their
+/// handles, so their assets are freed once nothing else uses them.
+pub struct UnloadAssetGroup(pub AssetGroup);
+
+impl Command for UnloadAssetGroup {
+    fn apply(self, world: &mut World) {
+        let group = self.0;
+        let mut handles = world.resource_mut::<ResourceHandles>();
+        if !handles.requested.contains(&group) {
+            return;
+        }
+        handles.requested.retain(|&requested| requested != group);
+        handles.waiting.retain(|waiting| waiting.group != group);
+        handles.finished.retain(|finished| finished.group != group);
+        handles.failed.retain(|failed| failed.group != group);
+        handles.failures.retain(|failure| failure.group != group);
+        let removes: Vec<RemoveResource> = handles
+            .registered
+            .iter()
+            .filter(|registered| registered.group == group)
+            .map(|registered| registered.remove)
+            .collect();
+        for remove in removes {
+            remove(world);
+        }
     }
 }
 
+/// A function that starts loading a registered resource, returning its handle
+/// and the assets it depends on.
+type LoadHandle = Arc<dyn Fn(&mut World) -> (UntypedHandle, Vec<UntypedAssetId>) + Send + Sync>;
 /// A function that inserts a loaded resource.
 type InsertLoadedResource = fn(&mut World, &UntypedHandle);
+/// A function that removes a resource when its group is unloaded.
+type RemoveResource = fn(&mut World);
+
+/// A resource that is loaded whenever its group is requested.
+struct RegisteredResource {
+    group: AssetGroup,
+    load: LoadHandle,
+    insert: InsertLoadedResource,
+    remove: RemoveResource,
+}
+
+/// A resource whose assets are still loading.
+struct WaitingResource {
+    group: AssetGroup,
+    handle: UntypedHandle,
+    dependencies: Vec<UntypedAssetId>,
+    /// How many of `dependencies` have loaded so far.
+    loaded: usize,
+    insert: InsertLoadedResource,
+}
+
+impl WaitingResource {
+    /// How many assets this resource counts as, counting a resource without
+    /// dependencies as one.
+    fn asset_count(&self) -> usize {
+        self.dependencies.len().max(1)
+    }
+}
+
+/// A resource that is done loading, successfully or not. Keeping its handle
+/// keeps its assets alive until the group is unloaded.
+struct DoneResource {
+    group: AssetGroup,
+    _handle: UntypedHandle,
+    asset_count: usize,
+}
+
+/// An asset that failed to load.
+#[derive(Debug, Clone)]
+pub struct AssetFailure {
+    pub group: AssetGroup,
+    /// The path of the asset, or of the asset whose dependency failed.
+    pub path: String,
+    pub error: String,
+}
 
 #[derive(Resource, Default)]
 pub struct ResourceHandles {
+    registered: Vec<RegisteredResource>,
+    /// The groups that have been requested and not unloaded since.
+    requested: Vec<AssetGroup>,
     // Use a queue for waiting assets so they can be cycled through and moved to
     // `finished` one at a time.
-    waiting: VecDeque<(UntypedHandle, InsertLoadedResource)>,
-    finished: Vec<UntypedHandle>,
+    waiting: VecDeque<WaitingResource>,
+    finished: Vec<DoneResource>,
+    /// Resources that can never be inserted because an asset failed to load.
+    failed: Vec<DoneResource>,
+    failures: Vec<AssetFailure>,
 }
 
 impl ResourceHandles {
-    /// Returns true if all requested [`Asset`]s have finished loading and are available as [`Resource`]s.
-    pub fn is_all_done(&self) -> bool {
-        self.waiting.is_empty()
+    /// Returns true if `group` has been requested and all of its [`Asset`]s
+    /// have finished loading and are available as [`Resource`]s.
+    pub fn is_done(&self, group: AssetGroup) -> bool {
+        self.requested.contains(&group)
+            && !self.waiting.iter().any(|waiting| waiting.group == group)
+            && !self.failed.iter().any(|failed| failed.group == group)
     }
+
+    /// How many of the assets of `group` have loaded so far, and how many there
+    /// are in total. A resource with several dependencies counts each of them.
+    pub fn progress(&self, group: AssetGroup) -> (usize, usize) {
+        let finished = self.finished.iter().filter(|done| done.group == group);
+        let waiting = self.waiting.iter().filter(|waiting| waiting.group == group);
+        let failed = self.failed.iter().filter(|done| done.group == group);
+        let finished_count: usize = finished.map(|done| done.asset_count).sum();
+        let loaded = finished_count + waiting.clone().map(|waiting| waiting.loaded).sum::<usize>();
+        let total = finished_count
+            + waiting.map(WaitingResource::asset_count).sum::<usize>()
+            + failed.map(|done| done.asset_count).sum::<usize>();
+        (loaded, total)
+    }
+
+    /// [`Self::progress`] as a fraction, from 0.0 to 1.0.
+    pub fn fraction(&self, group: AssetGroup) -> f32 {
+        let (loaded, total) = self.progress(group);
+        if total == 0 {
+            return 1.0;
+        }
+        loaded as f32 / total as f32
+    }
+
+    /// The assets of `group` that failed to load. Resources depending on them
+    /// will never be inserted.
+    pub fn failures(&self, group: AssetGroup) -> impl Iterator<Item = &AssetFailure> {
+        self.failures
+            .iter()
+            .filter(move |failure| failure.group == group)
+    }
+}
+
+/// Why `id` failed to load, if it did.
+fn failure(assets: &AssetServer, group: AssetGroup, id: UntypedAssetId) -> Option<AssetFailure> {
+    let error = match assets.get_load_state(id) {
+        Some(LoadState::Failed(error)) => error,
+        _ => match assets.get_recursive_dependency_load_state(id) {
+            Some(RecursiveDependencyLoadState::Failed(error)) => error,
+            _ => return None,
+        },
+    };
+    let path = assets
+        .get_path(id)
+        .map_or_else(|| format!("{id:?}"), |path| path.to_string());
+    Some(AssetFailure {
+        group,
+        path,
+        error: error.to_string(),
+    })
 }
 
 fn load_resource_assets(world: &mut World) {
     world.resource_scope(|world, mut resource_handles: Mut<ResourceHandles>| {
         world.resource_scope(|world, assets: Mut<AssetServer>| {
             for _ in 0..resource_handles.waiting.len() {
-                let (handle, insert_fn) = resource_handles.waiting.pop_front().unwrap();
-                if assets.is_loaded_with_dependencies(&handle) {
-                    insert_fn(world, &handle);
-                    resource_handles.finished.push(handle);
+                let mut waiting = resource_handles.waiting.pop_front().unwrap();
+                if assets.is_loaded_with_dependencies(&waiting.handle) {
+                    (waiting.insert)(world, &waiting.handle);
+                    resource_handles.finished.push(DoneResource {
+                        group: waiting.group,
+                        asset_count: waiting.asset_count(),
+                        _handle: waiting.handle,
+                    });
+                    continue;
+                }
+
+                let failures: Vec<AssetFailure> = waiting
+                    .dependencies
+                    .iter()
+                    .filter_map(|&id| failure(&assets, waiting.group, id))
+                    .collect();
+                if failures.is_empty() {
+                    waiting.loaded = waiting
+                        .dependencies
+                        .iter()
+                        .filter(|&&id| assets.is_loaded_with_dependencies(id))
+                        .count();
+                    resource_handles.waiting.push_back(waiting);
                 } else {
-                    resource_handles.waiting.push_back((handle, insert_fn));
+                    for failure in &failures {
+                        error!("Failed to load {}: {}", failure.path, failure.error);
+                    }
+                    resource_handles.failures.extend(failures);
+                    resource_handles.failed.push(DoneResource {
+                        group: waiting.group,
+                        asset_count: waiting.asset_count(),
+                        _handle: waiting.handle,
+                    });
                 }
             }
         });
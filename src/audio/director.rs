@@ -0,0 +1,173 @@
+//! A music director that owns the single music channel.
+//!
+//! Instead of spawning music themselves, screens and menus are mapped to a
+//! [`Track`] here. When the desired track changes, the old one fades out while
+//! the new one fades in. Music is ducked while the game is paused, and swaps to
+//! an intense track when the screen fills up with enemies.
+
+use bevy::{audio::Volume, prelude::*};
+
+use crate::{
+    AppSystems, Pause,
+    asset_tracking::{AssetCollection, AssetGroup, LoadResource},
+    audio::{AudioChannel, AudioVolumes, music},
+    gameplay::enemy::Enemy,
+    menus::Menu,
+    screens::Screen,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.load_resource::<MusicAssets>(AssetGroup::Gameplay);
+    app.load_resource::<CreditsMusicAssets>(AssetGroup::Credits);
+    app.init_resource::<DesiredTrack>();
+
+    app.add_systems(
+        Update,
+        (choose_track, switch_track, fade_music)
+            .chain()
+            .in_set(AppSystems::Update),
+    );
+}
+
+/// A piece of music the director can play.
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Reflect)]
+pub enum Track {
+    Gameplay,
+    Intense,
+    Credits,
+}
+
+#[derive(Resource, Asset, Clone, Reflect, AssetCollection)]
+#[reflect(Resource)]
+struct MusicAssets {
+    #[dependency]
+    #[asset(path = "audio/music/Fluffing A Duck.ogg")]
+    gameplay: Handle<AudioSource>,
+    #[dependency]
+    #[asset(path = "audio/music/intense.wav")]
+    intense: Handle<AudioSource>,
+}
+
+/// The credits music isn't played anywhere else, so it is only loaded while the
+/// credits are open.
+#[derive(Resource, Asset, Clone, Reflect, AssetCollection)]
+#[reflect(Resource)]
+struct CreditsMusicAssets {
+    #[dependency]
+    #[asset(path = "audio/music/Monkeys Spinning Monkeys.ogg")]
+    credits: Handle<AudioSource>,
+}
+
+/// How long a crossfade between two tracks takes.
+const CROSSFADE_SECS: f32 = 1.5;
+/// Music volume while the game is paused.
+const DUCKED_VOLUME: f32 = 0.3;
+/// Switch to the intense track when at least this many enemies are alive...
+const INTENSE_ENEMY_COUNT: usize = 30;
+/// ...and back once there are fewer than this many.
+const CALM_ENEMY_COUNT: usize = 15;
+
+/// The track the director wants to play. Music entities that don't play this
+/// track are faded out.
+#[derive(Resource, Default, Debug, PartialEq)]
+struct DesiredTrack(Option<Track>);
+
+/// A music entity playing a [`Track`], with its current fade level.
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct DirectedMusic {
+    track: Track,
+    level: f32,
+}
+
+fn choose_track(
+    screen: Res<State<Screen>>,
+    menu: Res<State<Menu>>,
+    enemy_query: Query<(), With<Enemy>>,
+    mut desired: ResMut<DesiredTrack>,
+) {
+    let current = desired.0;
+    let track = match (screen.get(), menu.get()) {
+        (_, Menu::Credits) => Some(Track::Credits),
+        (Screen::Gameplay, _) => {
+            let enemies = enemy_query.iter().len();
+            let intense = enemies >= INTENSE_ENEMY_COUNT
+                || (current == Some(Track::Intense) && enemies >= CALM_ENEMY_COUNT);
+            Some(if intense {
+                Track::Intense
+            } else {
+                Track::Gameplay
+            })
+        }
+        _ => None,
+    };
+    desired.set_if_neq(DesiredTrack(track));
+}
+
+fn switch_track(
+    mut commands: Commands,
+    desired: Res<DesiredTrack>,
+    music_assets: Option<Res<MusicAssets>>,
+    credits_music_assets: Option<Res<CreditsMusicAssets>>,
+    music_query: Query<&DirectedMusic>,
+) {
+    let Some(track) = desired.0 else {
+        return;
+    };
+    if music_query.iter().any(|music| music.track == track) {
+        return;
+    }
+    // The track starts once its assets have loaded.
+    let handle = match track {
+        Track::Gameplay => music_assets.map(|assets| assets.gameplay.clone()),
+        Track::Intense => music_assets.map(|assets| assets.intense.clone()),
+        Track::Credits => credits_music_assets.map(|assets| assets.credits.clone()),
+    };
+    let Some(handle) = handle else {
+        return;
+    };
+    commands.spawn((
+        Name::new("Music"),
+        music(handle),
+        DirectedMusic { track, level: 0.0 },
+    ));
+}
+
+fn fade_music(
+    mut commands: Commands,
+    time: Res<Time<Real>>,
+    desired: Res<DesiredTrack>,
+    pause: Res<State<Pause>>,
+    volumes: Res<AudioVolumes>,
+    mut music_query: Query<(
+        Entity,
+        &mut DirectedMusic,
+        &mut PlaybackSettings,
+        Option<&mut AudioSink>,
+    )>,
+) {
+    let step = time.delta_secs() / CROSSFADE_SECS;
+    for (entity, mut music, mut playback, sink) in &mut music_query {
+        let target = if Some(music.track) != desired.0 {
+            0.0
+        } else if pause.get().0 {
+            DUCKED_VOLUME
+        } else {
+            1.0
+        };
+        music.level = if music.level < target {
+            (music.level + step).min(target)
+        } else {
+            (music.level - step).max(target)
+        };
+
+        if music.level == 0.0 && target == 0.0 {
+            commands.entity(entity).despawn();
+            continue;
+        }
+        playback.volume = Volume::Linear(music.level);
+        if let Some(mut sink) = sink {
+            sink.set_volume(volumes.volume(AudioChannel::Music, &playback));
+        }
+    }
+}
@@ -0,0 +1,277 @@
+pub mod director;
+
+use bevy::{
+    audio::{AudioSinkPlayback, SpatialScale, Volume},
+    ecs::component::Mutable,
+    prelude::*,
+};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_plugins(director::plugin);
+
+    app.init_resource::<AudioVolumes>();
+    app.add_systems(
+        Update,
+        (
+            start_audio::<AudioSink>,
+            start_audio::<SpatialAudioSink>,
+            (
+                apply_audio_volumes::<AudioSink>,
+                apply_audio_volumes::<SpatialAudioSink>,
+            )
+                .run_if(resource_changed::<AudioVolumes>),
+        ),
+    );
+
+    app.add_observer(limit_sound_effect_instances);
+    app.add_observer(vary_pitch);
+}
+
+/// An organizational marker component that should be added to a spawned [`AudioPlayer`] if it's in the
+/// general "music" category (e.g. global background music, soundtrack).
+///
+/// This can then be used to query for and operate on sounds in that category.
+#[derive(Component, Reflect, Default)]
+#[reflect(Component)]
+pub struct Music;
+
+/// A music audio instance.
+pub fn music(handle: Handle<AudioSource>) -> impl Bundle {
+    (AudioPlayer(handle), PlaybackSettings::LOOP.paused(), Music)
+}
+
+/// An organizational marker component that should be added to a spawned [`AudioPlayer`] if it's in the
+/// general "sound effect" category (e.g. footsteps, the sound of a magic spell, a door opening).
+///
+/// This can then be used to query for and operate on sounds in that category.
+#[derive(Component, Reflect, Default)]
+#[reflect(Component)]
+pub struct SoundEffect;
+
+/// A sound effect audio instance.
+pub fn sound_effect(handle: Handle<AudioSource>) -> impl Bundle {
+    (
+        AudioPlayer(handle),
+        PlaybackSettings::DESPAWN.paused(),
+        SoundEffect,
+    )
+}
+
+/// A sound effect that plays at a position in the world, panning and attenuating
+/// relative to the [`SpatialListener`] on the camera.
+///
+/// Sound effects should be spawned at the position of the entity making the sound
+/// rather than as its child, so they don't get cut off when it is despawned.
+pub fn spatial_sound_effect(handle: Handle<AudioSource>, position: Vec2) -> impl Bundle {
+    (
+        AudioPlayer(handle),
+        PlaybackSettings::DESPAWN
+            .paused()
+            .with_spatial(true)
+            .with_spatial_scale(SpatialScale::new_2d(SPATIAL_SCALE)),
+        SoundEffect,
+        PitchVariation::default(),
+        Transform::from_translation(position.extend(0.0)),
+    )
+}
+
+/// How many world units (pixels) make up one unit of distance for spatial audio.
+const SPATIAL_SCALE: f32 = 1.0 / 100.0;
+
+/// The distance in world units between the ears of the [`SpatialListener`].
+pub const LISTENER_EAR_GAP: f32 = 400.0;
+
+/// The maximum number of instances of the same sound effect that may play at once.
+/// Further instances are dropped, to avoid a wall of noise when many enemies die
+/// at the same time.
+const MAX_SOUND_EFFECT_INSTANCES: usize = 4;
+
+fn limit_sound_effect_instances(
+    add: On<Add, SoundEffect>,
+    mut commands: Commands,
+    sound_query: Query<&AudioPlayer, With<SoundEffect>>,
+) {
+    let Ok(player) = sound_query.get(add.entity) else {
+        return;
+    };
+    let instances = sound_query
+        .iter()
+        .filter(|other| other.0.id() == player.0.id())
+        .count();
+    if instances > MAX_SOUND_EFFECT_INSTANCES {
+        commands.entity(add.entity).despawn();
+    }
+}
+
+/// Randomly varies the playback speed (and with it, the pitch) of a sound by up
+/// to the given fraction, so repeated sounds don't sound like a machine gun.
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+pub struct PitchVariation(pub f32);
+
+impl Default for PitchVariation {
+    fn default() -> Self {
+        Self(0.1)
+    }
+}
+
+fn vary_pitch(
+    add: On<Add, PitchVariation>,
+    mut sound_query: Query<(&PitchVariation, &mut PlaybackSettings)>,
+) {
+    let Ok((variation, mut playback)) = sound_query.get_mut(add.entity) else {
+        return;
+    };
+    if variation.0 > 0.0 {
+        playback.speed *= 1.0 + rand::rng().random_range(-variation.0..=variation.0);
+    }
+}
+
+/// An organizational marker component that should be added to a spawned [`AudioPlayer`] if it's in the
+/// "UI" category (e.g. button hovers and clicks).
+///
+/// This can then be used to query for and operate on sounds in that category.
+#[derive(Component, Reflect, Default)]
+#[reflect(Component)]
+pub struct UiSound;
+
+/// A UI sound instance.
+pub fn ui_sound(handle: Handle<AudioSource>) -> impl Bundle {
+    (
+        AudioPlayer(handle),
+        PlaybackSettings::DESPAWN.paused(),
+        UiSound,
+    )
+}
+
+/// A volume channel that audio can be routed through.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug, Reflect)]
+pub enum AudioChannel {
+    /// Applies to all audio.
+    Master,
+    Music,
+    SoundEffects,
+    Ui,
+}
+
+impl AudioChannel {
+    pub const ALL: [Self; 4] = [Self::Master, Self::Music, Self::SoundEffects, Self::Ui];
+
+    /// A human-readable name for the channel.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Master => "Master Volume",
+            Self::Music => "Music Volume",
+            Self::SoundEffects => "Effects Volume",
+            Self::Ui => "UI Volume",
+        }
+    }
+}
+
+/// The volume of a single [`AudioChannel`].
+#[derive(Copy, Clone, PartialEq, Debug, Reflect, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ChannelVolume {
+    /// Linear volume.
+    pub volume: f32,
+    pub muted: bool,
+}
+
+impl Default for ChannelVolume {
+    fn default() -> Self {
+        Self {
+            volume: 1.0,
+            muted: false,
+        }
+    }
+}
+
+impl ChannelVolume {
+    fn gain(self) -> f32 {
+        if self.muted { 0.0 } else { self.volume }
+    }
+}
+
+/// Per-channel volumes. The volume of a sound is the product of the master
+/// volume, its channel's volume and its own [`PlaybackSettings::volume`].
+///
+/// Unlike [`GlobalVolume`], changes apply to already-playing audio as well.
+#[derive(Resource, Clone, PartialEq, Debug, Default, Reflect, Serialize, Deserialize)]
+#[reflect(Resource)]
+#[serde(default)]
+pub struct AudioVolumes {
+    pub master: ChannelVolume,
+    pub music: ChannelVolume,
+    pub sound_effects: ChannelVolume,
+    pub ui: ChannelVolume,
+}
+
+impl AudioVolumes {
+    pub fn channel(&self, channel: AudioChannel) -> ChannelVolume {
+        match channel {
+            AudioChannel::Master => self.master,
+            AudioChannel::Music => self.music,
+            AudioChannel::SoundEffects => self.sound_effects,
+            AudioChannel::Ui => self.ui,
+        }
+    }
+
+    pub fn channel_mut(&mut self, channel: AudioChannel) -> &mut ChannelVolume {
+        match channel {
+            AudioChannel::Master => &mut self.master,
+            AudioChannel::Music => &mut self.music,
+            AudioChannel::SoundEffects => &mut self.sound_effects,
+            AudioChannel::Ui => &mut self.ui,
+        }
+    }
+
+    /// The combined volume for a sound routed through `channel`.
+    fn volume(&self, channel: AudioChannel, playback: &PlaybackSettings) -> Volume {
+        Volume::Linear(self.master.gain() * self.channel(channel).gain()) * playback.volume
+    }
+}
+
+/// Query data to find out which [`AudioChannel`] an audio entity belongs to.
+type ChannelMarkers = (Has<Music>, Has<SoundEffect>, Has<UiSound>);
+
+fn channel((music, sound_effect, ui): (bool, bool, bool)) -> Option<AudioChannel> {
+    if music {
+        Some(AudioChannel::Music)
+    } else if sound_effect {
+        Some(AudioChannel::SoundEffects)
+    } else if ui {
+        Some(AudioChannel::Ui)
+    } else {
+        None
+    }
+}
+
+/// Audio in a channel is spawned paused, so its volume can be set here before it
+/// is heard. Then it is started.
+fn start_audio<S: AudioSinkPlayback + Component<Mutability = Mutable>>(
+    volumes: Res<AudioVolumes>,
+    mut audio_query: Query<(&PlaybackSettings, &mut S, ChannelMarkers), Added<S>>,
+) {
+    for (playback, mut sink, markers) in &mut audio_query {
+        let Some(channel) = channel(markers) else {
+            continue;
+        };
+        sink.set_volume(volumes.volume(channel, playback));
+        sink.play();
+    }
+}
+
+/// Apply volume changes to already-running audio entities.
+fn apply_audio_volumes<S: AudioSinkPlayback + Component<Mutability = Mutable>>(
+    volumes: Res<AudioVolumes>,
+    mut audio_query: Query<(&PlaybackSettings, &mut S, ChannelMarkers)>,
+) {
+    for (playback, mut sink, markers) in &mut audio_query {
+        let Some(channel) = channel(markers) else {
+            continue;
+        };
+        sink.set_volume(volumes.volume(channel, playback));
+    }
+}
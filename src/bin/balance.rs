@@ -0,0 +1,393 @@
+//! Balance simulator: plays many headless runs with scripted bots and prints
+//! statistics about them, so balance changes can be compared.
+//!
+//! ```text
+//! cargo run --bin balance -- --runs 20 --bot all --format csv > before.csv
+//! ```
+//!
+//! The game has no enemy waves yet, so the simulator spawns enemies itself, at a
+//! random spot around the player every `--spawn-interval` seconds. There are no
+//! upgrades to pick either, so the bots only move, aim, shoot and reload.
+
+use std::{env, fmt::Write as _, process::ExitCode, str::FromStr};
+
+use bevy::prelude::*;
+use bevyjam7::{
+    gameplay::{
+        enemy::{Enemy, EnemyKind},
+        gun::Gun,
+        player::Player,
+        stats::RunStats,
+    },
+    headless::{FRAME_TIME, Simulation},
+    input::Action,
+    screens::Screen,
+};
+use rand::{Rng, SeedableRng, rngs::StdRng};
+
+const USAGE: &str = "\
+Usage: balance [OPTIONS]
+
+Options:
+  --runs <N>                Runs per bot [default: 10]
+  --bot <kite|stand|all>    Which bots to run [default: all]
+  --seed <N>                Seed of the first run, counting up from there [default: 0]
+  --max-seconds <S>         Stop runs that last longer than this [default: 300]
+  --spawn-interval <S>      Seconds between enemy spawns [default: 2]
+  --format <csv|json>       Output format [default: csv]
+  -h, --help                Print this help";
+
+/// How far from the player enemies are spawned.
+const SPAWN_DISTANCE: f32 = 400.0;
+
+fn main() -> ExitCode {
+    let options = match Options::parse(env::args().skip(1)) {
+        Ok(Some(options)) => options,
+        Ok(None) => {
+            println!("{USAGE}");
+            return ExitCode::SUCCESS;
+        }
+        Err(error) => {
+            eprintln!("{error}\n\n{USAGE}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let mut results = Vec::new();
+    for &bot in &options.bots {
+        for run in 0..options.runs {
+            let seed = options.seed + u64::from(run);
+            eprintln!(
+                "{} run {}/{} (seed {seed})",
+                bot.name(),
+                run + 1,
+                options.runs
+            );
+            results.push(simulate(bot, seed, &options));
+        }
+    }
+
+    let output = match options.format {
+        Format::Csv => to_csv(&results),
+        Format::Json => to_json(&results),
+    };
+    print!("{output}");
+    eprint!("{}", summary(&options.bots, &results));
+    ExitCode::SUCCESS
+}
+
+struct Options {
+    runs: u32,
+    bots: Vec<Bot>,
+    seed: u64,
+    max_seconds: f32,
+    spawn_interval: f32,
+    format: Format,
+}
+
+#[derive(Clone, Copy)]
+enum Format {
+    Csv,
+    Json,
+}
+
+impl Options {
+    /// Parse the command line arguments, or return `None` if help was requested.
+    fn parse(mut args: impl Iterator<Item = String>) -> Result<Option<Self>, String> {
+        let mut options = Self {
+            runs: 10,
+            bots: Bot::ALL.to_vec(),
+            seed: 0,
+            max_seconds: 300.0,
+            spawn_interval: 2.0,
+            format: Format::Csv,
+        };
+        while let Some(arg) = args.next() {
+            if arg == "-h" || arg == "--help" {
+                return Ok(None);
+            }
+            let value = args
+                .next()
+                .ok_or_else(|| format!("missing value for `{arg}`"))?;
+            match arg.as_str() {
+                "--runs" => options.runs = parse_number(&arg, &value)?,
+                "--seed" => options.seed = parse_number(&arg, &value)?,
+                "--max-seconds" => options.max_seconds = parse_number(&arg, &value)?,
+                "--spawn-interval" => options.spawn_interval = parse_number(&arg, &value)?,
+                "--bot" => {
+                    options.bots = match value.as_str() {
+                        "all" => Bot::ALL.to_vec(),
+                        name => vec![
+                            Bot::ALL
+                                .into_iter()
+                                .find(|bot| bot.name() == name)
+                                .ok_or_else(|| format!("unknown bot `{name}`"))?,
+                        ],
+                    }
+                }
+                "--format" => {
+                    options.format = match value.as_str() {
+                        "csv" => Format::Csv,
+                        "json" => Format::Json,
+                        format => return Err(format!("unknown format `{format}`")),
+                    }
+                }
+                _ => return Err(format!("unknown option `{arg}`")),
+            }
+        }
+        if options.spawn_interval <= 0.0 {
+            return Err("`--spawn-interval` must be positive".to_string());
+        }
+        Ok(Some(options))
+    }
+}
+
+fn parse_number<T: FromStr>(arg: &str, value: &str) -> Result<T, String> {
+    value
+        .parse()
+        .map_err(|_| format!("invalid value `{value}` for `{arg}`"))
+}
+
+/// A scripted player.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Bot {
+    /// Circles around the nearest enemy while shooting it.
+    Kite,
+    /// Stands still and shoots the nearest enemy.
+    Stand,
+}
+
+impl Bot {
+    const ALL: [Self; 2] = [Self::Kite, Self::Stand];
+
+    fn name(self) -> &'static str {
+        match self {
+            Self::Kite => "kite",
+            Self::Stand => "stand",
+        }
+    }
+
+    /// Decide this frame's input.
+    fn control(self, simulation: &mut Simulation) {
+        let world = simulation.world_mut();
+        let mut players = world.query_filtered::<&Transform, With<Player>>();
+        let Ok(player) = players
+            .single(world)
+            .map(|transform| transform.translation.xy())
+        else {
+            return;
+        };
+        let mut enemies = world.query_filtered::<&Transform, With<Enemy>>();
+        let nearest = enemies
+            .iter(world)
+            .map(|transform| transform.translation.xy() - player)
+            .min_by(|a, b| a.length_squared().total_cmp(&b.length_squared()));
+        let mut guns = world.query::<&Gun>();
+        let out_of_ammo = guns.single(world).is_ok_and(|gun| gun.ammo() == 0);
+
+        let movement = match (self, nearest) {
+            // Strafe around the enemy, backing off when it gets close.
+            (Self::Kite, Some(offset)) => {
+                let away = if offset.length() < 150.0 {
+                    -offset
+                } else {
+                    Vec2::ZERO
+                };
+                offset.perp().normalize_or_zero() + away.normalize_or_zero()
+            }
+            _ => Vec2::ZERO,
+        };
+        set_movement(simulation, movement);
+
+        simulation.aim(nearest);
+        set_pressed(simulation, Action::Fire, nearest.is_some());
+        set_pressed(simulation, Action::Reload, out_of_ammo);
+    }
+}
+
+fn set_pressed(simulation: &mut Simulation, action: Action, pressed: bool) {
+    if pressed {
+        simulation.press(action);
+    } else {
+        simulation.release(action);
+    }
+}
+
+/// Press the movement actions closest to `direction`, including diagonals.
+fn set_movement(simulation: &mut Simulation, direction: Vec2) {
+    // Ignore components under sin(22.5°), so there are eight directions.
+    let threshold = 0.38 * direction.length();
+    set_pressed(simulation, Action::MoveRight, direction.x > threshold);
+    set_pressed(simulation, Action::MoveLeft, direction.x < -threshold);
+    set_pressed(simulation, Action::MoveUp, direction.y > threshold);
+    set_pressed(simulation, Action::MoveDown, direction.y < -threshold);
+}
+
+/// The outcome of a simulated run.
+struct RunResult {
+    bot: Bot,
+    seed: u64,
+    /// How long the run lasted, in seconds.
+    time: f32,
+    died: bool,
+    damage_taken: f32,
+    /// Kills for each of [`EnemyKind::ALL`].
+    kills: Vec<u32>,
+    shots_fired: u32,
+    shots_hit: u32,
+    score: u32,
+}
+
+impl RunResult {
+    fn kills_per_minute(&self, kills: u32) -> f32 {
+        if self.time == 0.0 {
+            return 0.0;
+        }
+        kills as f32 * 60.0 / self.time
+    }
+}
+
+fn simulate(bot: Bot, seed: u64, options: &Options) -> RunResult {
+    let mut simulation = Simulation::new(seed);
+    let mut spawn_rng = StdRng::seed_from_u64(seed);
+    let max_frames = (options.max_seconds / FRAME_TIME.as_secs_f32()) as u32;
+    let spawn_frames = (options.spawn_interval / FRAME_TIME.as_secs_f32()).max(1.0) as u32;
+
+    let mut frame = 0;
+    while frame < max_frames && simulation.screen() == Screen::Gameplay {
+        if frame.is_multiple_of(spawn_frames) {
+            spawn_enemy_near_player(&mut simulation, &mut spawn_rng);
+        }
+        bot.control(&mut simulation);
+        simulation.step(1);
+        frame += 1;
+    }
+
+    let died = simulation.screen() == Screen::GameOver;
+    let stats = simulation.world().resource::<RunStats>();
+    RunResult {
+        bot,
+        seed,
+        time: stats.time.as_secs_f32(),
+        died,
+        damage_taken: stats.damage_taken,
+        kills: EnemyKind::ALL
+            .iter()
+            .map(|kind| stats.kills.get(kind).copied().unwrap_or_default())
+            .collect(),
+        shots_fired: stats.shots_fired,
+        shots_hit: stats.shots_hit,
+        score: stats.score(),
+    }
+}
+
+fn spawn_enemy_near_player(simulation: &mut Simulation, rng: &mut StdRng) {
+    let world = simulation.world_mut();
+    let mut players = world.query_filtered::<&Transform, With<Player>>();
+    let Ok(player) = players
+        .single(world)
+        .map(|transform| transform.translation.xy())
+    else {
+        return;
+    };
+    let direction = Vec2::from_angle(rng.random_range(0.0..std::f32::consts::TAU));
+    simulation.spawn_enemy(player + direction * SPAWN_DISTANCE);
+}
+
+fn to_csv(results: &[RunResult]) -> String {
+    let mut csv = "bot,seed,time,died,damage_taken".to_string();
+    for kind in EnemyKind::ALL {
+        let name = kind.name().to_lowercase();
+        write!(csv, ",{name}_kills,{name}_kills_per_minute").unwrap();
+    }
+    csv.push_str(",shots_fired,shots_hit,score\n");
+
+    for result in results {
+        write!(
+            csv,
+            "{},{},{:.2},{},{:.1}",
+            result.bot.name(),
+            result.seed,
+            result.time,
+            result.died,
+            result.damage_taken,
+        )
+        .unwrap();
+        for &kills in &result.kills {
+            write!(csv, ",{kills},{:.2}", result.kills_per_minute(kills)).unwrap();
+        }
+        writeln!(
+            csv,
+            ",{},{},{}",
+            result.shots_fired, result.shots_hit, result.score
+        )
+        .unwrap();
+    }
+    csv
+}
+
+fn to_json(results: &[RunResult]) -> String {
+    let runs: Vec<String> = results
+        .iter()
+        .map(|result| {
+            let kills: Vec<String> = EnemyKind::ALL
+                .iter()
+                .zip(&result.kills)
+                .map(|(kind, &kills)| {
+                    format!(
+                        r#""{}": {{ "total": {kills}, "per_minute": {:.2} }}"#,
+                        kind.name(),
+                        result.kills_per_minute(kills),
+                    )
+                })
+                .collect();
+            format!(
+                r#"  {{ "bot": "{}", "seed": {}, "time": {:.2}, "died": {}, "damage_taken": {:.1}, "kills": {{ {} }}, "shots_fired": {}, "shots_hit": {}, "score": {} }}"#,
+                result.bot.name(),
+                result.seed,
+                result.time,
+                result.died,
+                result.damage_taken,
+                kills.join(", "),
+                result.shots_fired,
+                result.shots_hit,
+                result.score,
+            )
+        })
+        .collect();
+    format!("[\n{}\n]\n", runs.join(",\n"))
+}
+
+/// Averages for each bot, for a quick look without opening the output.
+fn summary(bots: &[Bot], results: &[RunResult]) -> String {
+    let mut summary = String::new();
+    for &bot in bots {
+        let runs: Vec<&RunResult> = results.iter().filter(|result| result.bot == bot).collect();
+        if runs.is_empty() {
+            continue;
+        }
+        let mean = |value: &dyn Fn(&RunResult) -> f32| {
+            runs.iter().map(|result| value(result)).sum::<f32>() / runs.len() as f32
+        };
+        write!(
+            summary,
+            "{}: survived {:.1}s, took {:.1} damage, died in {:.0}% of runs",
+            bot.name(),
+            mean(&|result| result.time),
+            mean(&|result| result.damage_taken),
+            mean(&|result| if result.died { 100.0 } else { 0.0 }),
+        )
+        .unwrap();
+        for (index, kind) in EnemyKind::ALL.iter().enumerate() {
+            write!(
+                summary,
+                ", {:.1} {} kills/min",
+                mean(&|result| result.kills_per_minute(result.kills[index])),
+                kind.name(),
+            )
+            .unwrap();
+        }
+        summary.push('\n');
+    }
+    summary
+}
@@ -0,0 +1,525 @@
+//! Sprite sheet generator: renders the five platonic solids spinning, on the
+//! CPU, into sprite sheets for enemies.
+//!
+//! ```text
+//! cargo run --bin solids -- --solid all --frames 12 --size 32
+//! ```
+//!
+//! Each solid becomes a PNG with its frames side by side, and the sheets are
+//! listed in `solids.atlas.ron`, with a `<solid>_spin` clip each, in the format
+//! the sprite atlas loader reads. Faces are flat-shaded with the closest colour
+//! of the palette, so the sheets stay crisp pixel art.
+//!
+//! The game only loads `assets/images/sprites.atlas.ron`, so the output goes to
+//! `raw_assets/solids` rather than next to the sprites the game uses, and
+//! existing files are only replaced with `--force`. To use a sheet, copy its PNG
+//! into `assets/images` and its sprite and clip entries from `solids.atlas.ron`
+//! into `sprites.atlas.ron`, renaming them to the names the game expects, e.g.
+//! `tetrahedron` to `tetra`.
+
+use std::{
+    env,
+    f32::consts::TAU,
+    fmt::Write as _,
+    fs::{self, File},
+    io::BufWriter,
+    path::{Path, PathBuf},
+    process::ExitCode,
+    str::FromStr,
+};
+
+const USAGE: &str = "\
+Usage: solids [OPTIONS]
+
+Options:
+  --solid <NAME|all>        tetrahedron, cube, octahedron, dodecahedron, icosahedron [default: all]
+  --frames <N>              Frames per spin [default: 12]
+  --size <PX>               Width and height of each frame [default: 32]
+  --palette <HEX,...>       Face colours, from darkest to lightest [default: 2b2b3d,3d4999,4666bf,6299d1,9fd3e6]
+  --outline <HEX|none>      Colour of the one pixel outline [default: 1a1a26]
+  --frame-millis <MS>       How long each frame of the spin clips lasts [default: 100]
+  --out <DIR>               Where to write the sheets and metadata [default: raw_assets/solids]
+  --force                   Overwrite existing files
+  -h, --help                Print this help";
+
+/// The direction light comes from, towards the top left and the viewer.
+const LIGHT: Vec3 = [-0.45, 0.55, 0.7];
+
+/// How far the spin axis leans towards the viewer, in radians, so the top of
+/// the solid shows too.
+const TILT: f32 = 0.45;
+
+fn main() -> ExitCode {
+    let options = match Options::parse(env::args().skip(1)) {
+        Ok(Some(options)) => options,
+        Ok(None) => {
+            println!("{USAGE}");
+            return ExitCode::SUCCESS;
+        }
+        Err(error) => {
+            eprintln!("{error}\n\n{USAGE}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    if let Err(error) = fs::create_dir_all(&options.out) {
+        eprintln!("can't create {}: {error}", options.out.display());
+        return ExitCode::FAILURE;
+    }
+    let atlas_path = options.out.join("solids.atlas.ron");
+    let sheet_path = |solid: Solid| options.out.join(format!("{}.png", solid.name()));
+    if !options.force {
+        let existing = options
+            .solids
+            .iter()
+            .map(|&solid| sheet_path(solid))
+            .chain([atlas_path.clone()])
+            .find(|path| path.exists());
+        if let Some(path) = existing {
+            eprintln!(
+                "{} already exists, pass `--force` to overwrite it",
+                path.display()
+            );
+            return ExitCode::FAILURE;
+        }
+    }
+    for &solid in &options.solids {
+        let path = sheet_path(solid);
+        let pixels = render_sheet(&solid.mesh(), &options);
+        if let Err(error) = write_png(&path, options.size * options.frames, options.size, &pixels) {
+            eprintln!("can't write {}: {error}", path.display());
+            return ExitCode::FAILURE;
+        }
+        eprintln!("wrote {}", path.display());
+    }
+    if let Err(error) = fs::write(&atlas_path, atlas_metadata(&options)) {
+        eprintln!("can't write {}: {error}", atlas_path.display());
+        return ExitCode::FAILURE;
+    }
+    eprintln!("wrote {}", atlas_path.display());
+    ExitCode::SUCCESS
+}
+
+struct Options {
+    solids: Vec<Solid>,
+    frames: u32,
+    size: u32,
+    palette: Vec<Rgba>,
+    outline: Option<Rgba>,
+    frame_millis: u32,
+    out: PathBuf,
+    force: bool,
+}
+
+impl Options {
+    /// Parse the command line arguments, or return `None` if help was requested.
+    fn parse(mut args: impl Iterator<Item = String>) -> Result<Option<Self>, String> {
+        let mut options = Self {
+            solids: Solid::ALL.to_vec(),
+            frames: 12,
+            size: 32,
+            palette: parse_palette("2b2b3d,3d4999,4666bf,6299d1,9fd3e6")?,
+            outline: Some(parse_color("1a1a26")?),
+            frame_millis: 100,
+            out: PathBuf::from("raw_assets/solids"),
+            force: false,
+        };
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "-h" | "--help" => return Ok(None),
+                "--force" => {
+                    options.force = true;
+                    continue;
+                }
+                _ => {}
+            }
+            let value = args
+                .next()
+                .ok_or_else(|| format!("missing value for `{arg}`"))?;
+            match arg.as_str() {
+                "--frames" => options.frames = parse_number(&arg, &value)?,
+                "--size" => options.size = parse_number(&arg, &value)?,
+                "--frame-millis" => options.frame_millis = parse_number(&arg, &value)?,
+                "--palette" => options.palette = parse_palette(&value)?,
+                "--outline" => {
+                    options.outline = match value.as_str() {
+                        "none" => None,
+                        color => Some(parse_color(color)?),
+                    }
+                }
+                "--out" => options.out = PathBuf::from(value),
+                "--solid" => {
+                    options.solids = match value.as_str() {
+                        "all" => Solid::ALL.to_vec(),
+                        name => vec![
+                            Solid::ALL
+                                .into_iter()
+                                .find(|solid| solid.name() == name)
+                                .ok_or_else(|| format!("unknown solid `{name}`"))?,
+                        ],
+                    }
+                }
+                _ => return Err(format!("unknown option `{arg}`")),
+            }
+        }
+        if options.frames == 0 {
+            return Err("`--frames` must be positive".to_string());
+        }
+        if options.size < 4 {
+            return Err("`--size` must be at least 4".to_string());
+        }
+        Ok(Some(options))
+    }
+}
+
+fn parse_number<T: FromStr>(arg: &str, value: &str) -> Result<T, String> {
+    value
+        .parse()
+        .map_err(|_| format!("invalid value `{value}` for `{arg}`"))
+}
+
+type Rgba = [u8; 4];
+
+/// Parse a colour like `4666bf` or `#4666bf`.
+fn parse_color(value: &str) -> Result<Rgba, String> {
+    let hex = value.trim().trim_start_matches('#');
+    let channel = |index: usize| {
+        hex.get(index * 2..index * 2 + 2)
+            .and_then(|channel| u8::from_str_radix(channel, 16).ok())
+    };
+    match (hex.len(), channel(0), channel(1), channel(2)) {
+        (6, Some(r), Some(g), Some(b)) => Ok([r, g, b, 255]),
+        _ => Err(format!("invalid colour `{value}`")),
+    }
+}
+
+fn parse_palette(value: &str) -> Result<Vec<Rgba>, String> {
+    let palette = value
+        .split(',')
+        .map(parse_color)
+        .collect::<Result<Vec<_>, _>>()?;
+    if palette.is_empty() {
+        return Err("the palette needs at least one colour".to_string());
+    }
+    Ok(palette)
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Solid {
+    Tetrahedron,
+    Cube,
+    Octahedron,
+    Dodecahedron,
+    Icosahedron,
+}
+
+impl Solid {
+    const ALL: [Self; 5] = [
+        Self::Tetrahedron,
+        Self::Cube,
+        Self::Octahedron,
+        Self::Dodecahedron,
+        Self::Icosahedron,
+    ];
+
+    fn name(self) -> &'static str {
+        match self {
+            Self::Tetrahedron => "tetrahedron",
+            Self::Cube => "cube",
+            Self::Octahedron => "octahedron",
+            Self::Dodecahedron => "dodecahedron",
+            Self::Icosahedron => "icosahedron",
+        }
+    }
+
+    fn vertices(self) -> Vec<Vec3> {
+        // The golden ratio.
+        let phi = (1.0 + 5f32.sqrt()) / 2.0;
+        match self {
+            Self::Tetrahedron => vec![
+                [1.0, 1.0, 1.0],
+                [1.0, -1.0, -1.0],
+                [-1.0, 1.0, -1.0],
+                [-1.0, -1.0, 1.0],
+            ],
+            Self::Cube => signs([1.0, 1.0, 1.0]),
+            Self::Octahedron => [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]]
+                .into_iter()
+                .flat_map(signs)
+                .collect(),
+            Self::Dodecahedron => {
+                let mut vertices = signs([1.0, 1.0, 1.0]);
+                vertices.extend(cyclic([0.0, 1.0 / phi, phi]).into_iter().flat_map(signs));
+                vertices
+            }
+            Self::Icosahedron => cyclic([0.0, 1.0, phi])
+                .into_iter()
+                .flat_map(signs)
+                .collect(),
+        }
+    }
+
+    /// The solid's faces, scaled to fit in a unit sphere.
+    fn mesh(self) -> Vec<Vec<Vec3>> {
+        let vertices: Vec<Vec3> = self
+            .vertices()
+            .into_iter()
+            .map(|vertex| scale(vertex, 1.0 / length(vertex)))
+            .collect();
+        convex_faces(&vertices)
+    }
+}
+
+/// Every combination of signs of `vertex`'s non-zero coordinates.
+fn signs(vertex: Vec3) -> Vec<Vec3> {
+    let mut vertices = vec![vertex];
+    for axis in 0..3 {
+        if vertex[axis] != 0.0 {
+            let flipped: Vec<Vec3> = vertices
+                .iter()
+                .map(|&vertex| {
+                    let mut vertex = vertex;
+                    vertex[axis] = -vertex[axis];
+                    vertex
+                })
+                .collect();
+            vertices.extend(flipped);
+        }
+    }
+    vertices
+}
+
+/// `vertex` with its coordinates rotated through all three positions.
+fn cyclic([x, y, z]: Vec3) -> [Vec3; 3] {
+    [[x, y, z], [y, z, x], [z, x, y]]
+}
+
+/// The faces of the convex hull of `vertices`, each with its vertices in
+/// counterclockwise order seen from outside. Every vertex must lie on the hull,
+/// which holds for the platonic solids.
+fn convex_faces(vertices: &[Vec3]) -> Vec<Vec<Vec3>> {
+    const EPSILON: f32 = 1e-4;
+    let mut normals: Vec<Vec3> = Vec::new();
+    let mut faces = Vec::new();
+    for (i, &a) in vertices.iter().enumerate() {
+        for (j, &b) in vertices.iter().enumerate().skip(i + 1) {
+            for &c in vertices.iter().skip(j + 1) {
+                let mut normal = cross(sub(b, a), sub(c, a));
+                if length(normal) < EPSILON {
+                    continue;
+                }
+                normal = normalize(normal);
+                let offset = dot(normal, a);
+                if offset < 0.0 {
+                    normal = scale(normal, -1.0);
+                }
+                let offset = offset.abs();
+                // A face's plane has every vertex on or behind it.
+                if vertices.iter().any(|&v| dot(normal, v) > offset + EPSILON)
+                    || normals.iter().any(|&n| dot(n, normal) > 1.0 - EPSILON)
+                {
+                    continue;
+                }
+                normals.push(normal);
+                let mut face: Vec<Vec3> = vertices
+                    .iter()
+                    .copied()
+                    .filter(|&v| (dot(normal, v) - offset).abs() < EPSILON)
+                    .collect();
+                sort_around(&mut face, normal);
+                faces.push(face);
+            }
+        }
+    }
+    faces
+}
+
+/// Sort the vertices of a convex face counterclockwise around `normal`.
+fn sort_around(face: &mut [Vec3], normal: Vec3) {
+    let center = scale(
+        face.iter().fold([0.0; 3], |sum, &v| add(sum, v)),
+        1.0 / face.len() as f32,
+    );
+    let u = normalize(sub(face[0], center));
+    let v = cross(normal, u);
+    face.sort_by(|&a, &b| {
+        let angle = |p: Vec3| {
+            let offset = sub(p, center);
+            dot(offset, v).atan2(dot(offset, u))
+        };
+        angle(a).total_cmp(&angle(b))
+    });
+}
+
+/// Render every frame of one spin side by side, as RGBA pixels.
+fn render_sheet(faces: &[Vec<Vec3>], options: &Options) -> Vec<u8> {
+    let size = options.size as usize;
+    let width = size * options.frames as usize;
+    let mut pixels = vec![0; width * size * 4];
+    for frame in 0..options.frames {
+        let angle = TAU * frame as f32 / options.frames as f32;
+        let frame_pixels = render_frame(faces, angle, options);
+        for y in 0..size {
+            let row = &frame_pixels[y * size * 4..(y + 1) * size * 4];
+            let start = (y * width + frame as usize * size) * 4;
+            pixels[start..start + size * 4].copy_from_slice(row);
+        }
+    }
+    pixels
+}
+
+/// Render the solid turned `angle` radians around its tilted spin axis.
+fn render_frame(faces: &[Vec<Vec3>], angle: f32, options: &Options) -> Vec<u8> {
+    let size = options.size as usize;
+    let light = normalize(LIGHT);
+    // Leave room for the outline.
+    let radius = size as f32 / 2.0 - 1.5;
+    let center = size as f32 / 2.0;
+    let mut pixels = vec![0; size * size * 4];
+    let mut filled = vec![false; size * size];
+
+    for face in faces {
+        let rotated: Vec<Vec3> = face
+            .iter()
+            .map(|&v| rotate_x(rotate_y(v, angle), TILT))
+            .collect();
+        let normal = normalize(cross(
+            sub(rotated[1], rotated[0]),
+            sub(rotated[2], rotated[0]),
+        ));
+        // The viewer looks down the negative z axis, so faces pointing away are
+        // hidden behind the front ones. The solid is convex, so nothing else
+        // can overlap.
+        if normal[2] <= 0.0 {
+            continue;
+        }
+        let brightness = dot(normal, light).max(0.0);
+        let shade = (brightness * (options.palette.len() - 1) as f32).round() as usize;
+        let color = options.palette[shade.min(options.palette.len() - 1)];
+
+        // Project to pixels, with y pointing down.
+        let points: Vec<[f32; 2]> = rotated
+            .iter()
+            .map(|v| [center + v[0] * radius, center - v[1] * radius])
+            .collect();
+        for y in 0..size {
+            for x in 0..size {
+                let pixel = [x as f32 + 0.5, y as f32 + 0.5];
+                if inside(&points, pixel) {
+                    pixels[(y * size + x) * 4..][..4].copy_from_slice(&color);
+                    filled[y * size + x] = true;
+                }
+            }
+        }
+    }
+
+    if let Some(outline) = options.outline {
+        for y in 0..size {
+            for x in 0..size {
+                let touches_solid = [(-1, 0), (1, 0), (0, -1), (0, 1)].iter().any(|(dx, dy)| {
+                    let (nx, ny) = (x as isize + dx, y as isize + dy);
+                    (0..size as isize).contains(&nx)
+                        && (0..size as isize).contains(&ny)
+                        && filled[ny as usize * size + nx as usize]
+                });
+                if !filled[y * size + x] && touches_solid {
+                    pixels[(y * size + x) * 4..][..4].copy_from_slice(&outline);
+                }
+            }
+        }
+    }
+    pixels
+}
+
+/// Whether `point` is inside a front face. Front faces are counterclockwise
+/// to the viewer, so with y pointing down, `point` is on the negative side of
+/// every edge.
+fn inside(polygon: &[[f32; 2]], point: [f32; 2]) -> bool {
+    polygon.iter().enumerate().all(|(index, &a)| {
+        let b = polygon[(index + 1) % polygon.len()];
+        let edge = (b[0] - a[0]) * (point[1] - a[1]) - (b[1] - a[1]) * (point[0] - a[0]);
+        edge <= 0.0
+    })
+}
+
+fn write_png(path: &Path, width: u32, height: u32, pixels: &[u8]) -> Result<(), String> {
+    let file = File::create(path).map_err(|error| error.to_string())?;
+    let mut encoder = png::Encoder::new(BufWriter::new(file), width, height);
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+    let mut writer = encoder.write_header().map_err(|error| error.to_string())?;
+    writer
+        .write_image_data(pixels)
+        .map_err(|error| error.to_string())?;
+    writer.finish().map_err(|error| error.to_string())
+}
+
+/// The `.atlas.ron` file listing the sheets.
+fn atlas_metadata(options: &Options) -> String {
+    let mut ron = String::from(
+        "// Generated by `cargo run --bin solids`. Changes will be overwritten.\n(\n    sprites: {\n",
+    );
+    for solid in &options.solids {
+        let name = solid.name();
+        let _ = writeln!(
+            ron,
+            "        \"{name}\": (path: \"{name}.png\", columns: {}),",
+            options.frames
+        );
+    }
+    ron.push_str("    },\n    clips: {\n");
+    for solid in &options.solids {
+        let name = solid.name();
+        let _ = writeln!(
+            ron,
+            "        \"{name}_spin\": (sprite: \"{name}\", frame_millis: {}),",
+            options.frame_millis
+        );
+    }
+    ron.push_str("    },\n)\n");
+    ron
+}
+
+type Vec3 = [f32; 3];
+
+fn add(a: Vec3, b: Vec3) -> Vec3 {
+    [a[0] + b[0], a[1] + b[1], a[2] + b[2]]
+}
+
+fn sub(a: Vec3, b: Vec3) -> Vec3 {
+    [a[0] - b[0], a[1] - b[1], a[2] - b[2]]
+}
+
+fn scale(a: Vec3, factor: f32) -> Vec3 {
+    [a[0] * factor, a[1] * factor, a[2] * factor]
+}
+
+fn dot(a: Vec3, b: Vec3) -> f32 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+fn cross(a: Vec3, b: Vec3) -> Vec3 {
+    [
+        a[1] * b[2] - a[2] * b[1],
+        a[2] * b[0] - a[0] * b[2],
+        a[0] * b[1] - a[1] * b[0],
+    ]
+}
+
+fn length(a: Vec3) -> f32 {
+    dot(a, a).sqrt()
+}
+
+fn normalize(a: Vec3) -> Vec3 {
+    scale(a, 1.0 / length(a))
+}
+
+fn rotate_y([x, y, z]: Vec3, angle: f32) -> Vec3 {
+    let (sin, cos) = angle.sin_cos();
+    [x * cos + z * sin, y, -x * sin + z * cos]
+}
+
+fn rotate_x([x, y, z]: Vec3, angle: f32) -> Vec3 {
+    let (sin, cos) = angle.sin_cos();
+    [x, y * cos - z * sin, y * sin + z * cos]
+}
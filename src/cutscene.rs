@@ -0,0 +1,469 @@
+//! A data-driven cutscene player.
+//!
+//! A [`Cutscene`] is a timeline loaded from a `.cutscene.ron` file, with
+//! keyframes for the camera's position and zoom, sprites from the sprite atlas
+//! that fade in and out, captions and sound cues:
+//!
+//! ```ron
+//! (
+//!     duration: 4.0,
+//!     camera: [(time: 0.0, position: (0.0, 0.0), zoom: 1.0), (time: 4.0, position: (0.0, 0.0), zoom: 2.0)],
+//!     sprites: [(sprite: "ducky", frame: 0, fade: [(time: 0.0, alpha: 0.0), (time: 1.0, alpha: 1.0)])],
+//!     captions: [(start: 0.5, end: 3.5, text: "Quack.")],
+//!     sounds: [(time: 1.0, path: "audio/sound_effects/pop.wav")],
+//! )
+//! ```
+//!
+//! Keys may be listed in any order. Spawn a [`CutscenePlayer`] to play one,
+//! which starts once the sprite atlas has loaded. Sprites and the camera are
+//! placed relative to the player's [`Transform`], so the same cutscene can play
+//! anywhere in the world. Any key skips it, and [`CutsceneFinished`] is
+//! triggered on the player when it ends, after which it despawns and the camera
+//! goes back to where it was.
+
+use bevy::{
+    asset::{AssetLoader, LoadContext, io::Reader},
+    prelude::*,
+};
+use serde::Deserialize;
+
+use crate::{
+    AppSystems,
+    audio::sound_effect,
+    input::just_pressed_binding,
+    sprite_atlas::SpriteAtlas,
+    theme::palette::{HEADER_TEXT, LABEL_TEXT},
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_asset::<Cutscene>();
+    app.init_asset_loader::<CutsceneLoader>();
+
+    app.add_systems(
+        Update,
+        (start_cutscenes, skip_cutscenes, play_cutscenes)
+            .chain()
+            .in_set(AppSystems::Update),
+    );
+}
+
+/// A timeline of camera moves, sprites, captions and sounds.
+#[derive(Asset, TypePath, Debug)]
+pub struct Cutscene {
+    /// How long the cutscene lasts, in seconds.
+    pub duration: f32,
+    camera: Vec<CameraKey>,
+    sprites: Vec<CutsceneSprite>,
+    captions: Vec<Caption>,
+    sounds: Vec<SoundCue>,
+}
+
+/// Where the camera looks at a point in time. The camera eases between keys.
+#[derive(Deserialize, Clone, Copy, Debug)]
+struct CameraKey {
+    time: f32,
+    position: Vec2,
+    zoom: f32,
+}
+
+#[derive(Debug)]
+struct CutsceneSprite {
+    /// The sprite's name in the [`SpriteAtlas`].
+    sprite: String,
+    /// Which of the sprite's frames to show.
+    frame: usize,
+    position: Vec2,
+    scale: f32,
+    z: f32,
+    /// How opaque the sprite is over time, fading linearly between keys. A
+    /// sprite without keys is always opaque.
+    fade: Vec<FadeKey>,
+}
+
+#[derive(Deserialize, Clone, Copy, Debug)]
+struct FadeKey {
+    time: f32,
+    alpha: f32,
+}
+
+/// Text shown at the bottom of the screen from `start` to `end`.
+#[derive(Deserialize, Clone, Debug)]
+struct Caption {
+    start: f32,
+    end: f32,
+    text: String,
+}
+
+#[derive(Debug)]
+struct SoundCue {
+    time: f32,
+    sound: Handle<AudioSource>,
+}
+
+/// How long captions take to fade in and out, in seconds.
+const CAPTION_FADE_SECS: f32 = 0.3;
+
+/// The contents of a `.cutscene.ron` file.
+#[derive(Deserialize)]
+struct CutsceneMeta {
+    duration: f32,
+    #[serde(default)]
+    camera: Vec<CameraKey>,
+    #[serde(default)]
+    sprites: Vec<SpriteMeta>,
+    #[serde(default)]
+    captions: Vec<Caption>,
+    #[serde(default)]
+    sounds: Vec<SoundMeta>,
+}
+
+#[derive(Deserialize)]
+struct SpriteMeta {
+    /// The sprite's name in the [`SpriteAtlas`].
+    sprite: String,
+    #[serde(default)]
+    frame: usize,
+    #[serde(default)]
+    position: Vec2,
+    #[serde(default = "one")]
+    scale: f32,
+    #[serde(default)]
+    z: f32,
+    #[serde(default)]
+    fade: Vec<FadeKey>,
+}
+
+fn one() -> f32 {
+    1.0
+}
+
+#[derive(Deserialize)]
+struct SoundMeta {
+    time: f32,
+    /// The sound's path in the assets folder.
+    path: String,
+}
+
+#[derive(Default, TypePath)]
+struct CutsceneLoader;
+
+impl AssetLoader for CutsceneLoader {
+    type Asset = Cutscene;
+    type Settings = ();
+    type Error = BevyError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        load_context: &mut LoadContext<'_>,
+    ) -> Result<Cutscene, BevyError> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        let mut meta: CutsceneMeta = ron::de::from_bytes(&bytes)?;
+
+        // Sampling keys expects them in order.
+        meta.camera.sort_by(|a, b| a.time.total_cmp(&b.time));
+        let sprites = meta
+            .sprites
+            .into_iter()
+            .map(|mut sprite| {
+                sprite.fade.sort_by(|a, b| a.time.total_cmp(&b.time));
+                CutsceneSprite {
+                    sprite: sprite.sprite,
+                    frame: sprite.frame,
+                    position: sprite.position,
+                    scale: sprite.scale,
+                    z: sprite.z,
+                    fade: sprite.fade,
+                }
+            })
+            .collect();
+        let mut sounds: Vec<SoundCue> = meta
+            .sounds
+            .into_iter()
+            .map(|sound| SoundCue {
+                time: sound.time,
+                sound: load_context.load(sound.path),
+            })
+            .collect();
+        sounds.sort_by(|a, b| a.time.total_cmp(&b.time));
+
+        Ok(Cutscene {
+            duration: meta.duration,
+            camera: meta.camera,
+            sprites,
+            captions: meta.captions,
+            sounds,
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["cutscene.ron"]
+    }
+}
+
+/// Plays a [`Cutscene`] once it has loaded, then despawns.
+#[derive(Component, Debug)]
+#[require(Transform, Visibility)]
+pub struct CutscenePlayer {
+    cutscene: Handle<Cutscene>,
+    /// How far into the cutscene the player is, in seconds.
+    elapsed: f32,
+    /// The index of the next sound cue to play.
+    next_sound: usize,
+    /// What the player spawned once the cutscene loaded.
+    playing: Option<Playing>,
+}
+
+#[derive(Debug)]
+struct Playing {
+    /// The camera's transform and projection scale before the cutscene.
+    camera: (Transform, f32),
+    caption_root: Entity,
+    caption: Entity,
+}
+
+impl CutscenePlayer {
+    pub fn new(cutscene: Handle<Cutscene>) -> Self {
+        Self {
+            cutscene,
+            elapsed: 0.0,
+            next_sound: 0,
+            playing: None,
+        }
+    }
+
+    /// Whether the cutscene has loaded and started playing.
+    pub fn is_playing(&self) -> bool {
+        self.playing.is_some()
+    }
+}
+
+/// Triggered on a [`CutscenePlayer`] when its cutscene ends or is skipped.
+#[derive(EntityEvent, Debug)]
+pub struct CutsceneFinished {
+    pub entity: Entity,
+}
+
+/// A sprite of a playing cutscene, by index into [`Cutscene::sprites`].
+#[derive(Component, Debug)]
+struct CutsceneActor(usize);
+
+fn start_cutscenes(
+    mut commands: Commands,
+    cutscenes: Res<Assets<Cutscene>>,
+    sprites: If<Res<SpriteAtlas>>,
+    mut player_query: Query<(Entity, &mut CutscenePlayer)>,
+    camera: Single<(&Transform, &Projection), With<Camera2d>>,
+) {
+    let (camera_transform, projection) = *camera;
+    let camera_scale = match projection {
+        Projection::Orthographic(orthographic) => orthographic.scale,
+        _ => 1.0,
+    };
+    for (entity, mut player) in &mut player_query {
+        if player.playing.is_some() {
+            continue;
+        }
+        let Some(cutscene) = cutscenes.get(&player.cutscene) else {
+            continue;
+        };
+
+        for (index, sprite) in cutscene.sprites.iter().enumerate() {
+            let Some(atlas_sprite) = sprites.frame(&sprite.sprite, sprite.frame) else {
+                warn!(
+                    "Cutscene sprite {:?} has no frame {}",
+                    sprite.sprite, sprite.frame
+                );
+                continue;
+            };
+            let actor = commands
+                .spawn((
+                    Name::new("Cutscene Sprite"),
+                    CutsceneActor(index),
+                    atlas_sprite,
+                    Transform::from_translation(sprite.position.extend(sprite.z))
+                        .with_scale(Vec3::splat(sprite.scale)),
+                ))
+                .id();
+            commands.entity(entity).add_child(actor);
+        }
+
+        let mut caption = Entity::PLACEHOLDER;
+        let caption_root = commands
+            .spawn((
+                Name::new("Cutscene Captions"),
+                Node {
+                    position_type: PositionType::Absolute,
+                    width: percent(100),
+                    height: percent(100),
+                    flex_direction: FlexDirection::Column,
+                    justify_content: JustifyContent::End,
+                    align_items: AlignItems::Center,
+                    padding: UiRect::all(px(24)),
+                    row_gap: px(16),
+                    ..default()
+                },
+                GlobalZIndex(1),
+                Pickable::IGNORE,
+            ))
+            .with_children(|parent| {
+                caption = parent
+                    .spawn((
+                        Name::new("Caption"),
+                        Text::default(),
+                        TextFont::from_font_size(32.0),
+                        TextColor(HEADER_TEXT.with_alpha(0.0)),
+                        TextLayout::new_with_justify(Justify::Center),
+                    ))
+                    .id();
+                parent.spawn((
+                    Name::new("Skip Hint"),
+                    Text::new("Press any key to skip"),
+                    TextFont::from_font_size(16.0),
+                    TextColor(LABEL_TEXT.with_alpha(0.6)),
+                ));
+            })
+            .id();
+
+        player.playing = Some(Playing {
+            camera: (*camera_transform, camera_scale),
+            caption_root,
+            caption,
+        });
+    }
+}
+
+/// Any key, mouse button or gamepad button skips to the end.
+fn skip_cutscenes(
+    keys: Res<ButtonInput<KeyCode>>,
+    mouse: Res<ButtonInput<MouseButton>>,
+    gamepads: Query<&Gamepad>,
+    cutscenes: Res<Assets<Cutscene>>,
+    mut player_query: Query<&mut CutscenePlayer>,
+) {
+    if just_pressed_binding(&keys, &mouse, &gamepads).is_none() {
+        return;
+    }
+    for mut player in &mut player_query {
+        if player.playing.is_some()
+            && let Some(cutscene) = cutscenes.get(&player.cutscene)
+        {
+            player.elapsed = cutscene.duration;
+            // Skipping shouldn't set off every remaining sound at once.
+            player.next_sound = cutscene.sounds.len();
+        }
+    }
+}
+
+fn play_cutscenes(
+    mut commands: Commands,
+    time: Res<Time>,
+    cutscenes: Res<Assets<Cutscene>>,
+    mut player_query: Query<(Entity, &mut CutscenePlayer, &Transform, Option<&Children>)>,
+    mut actor_query: Query<(&CutsceneActor, &mut Sprite)>,
+    mut caption_query: Query<(&mut Text, &mut TextColor)>,
+    camera: Single<(&mut Transform, &mut Projection), (With<Camera2d>, Without<CutscenePlayer>)>,
+) {
+    let (mut camera_transform, mut projection) = camera.into_inner();
+    for (entity, mut player, origin, children) in &mut player_query {
+        let Some(cutscene) = cutscenes.get(&player.cutscene) else {
+            continue;
+        };
+        let Some(playing) = &player.playing else {
+            continue;
+        };
+        let (caption_root, caption, camera_before) =
+            (playing.caption_root, playing.caption, playing.camera);
+
+        player.elapsed += time.delta_secs();
+        let elapsed = player.elapsed;
+
+        if elapsed >= cutscene.duration {
+            *camera_transform = camera_before.0;
+            set_camera_scale(&mut projection, camera_before.1);
+            commands.entity(caption_root).despawn();
+            commands.trigger(CutsceneFinished { entity });
+            commands.entity(entity).despawn();
+            continue;
+        }
+
+        if let Some((position, zoom)) = sample_camera(&cutscene.camera, elapsed) {
+            let z = camera_transform.translation.z;
+            camera_transform.translation = (origin.translation.xy() + position).extend(z);
+            set_camera_scale(&mut projection, 1.0 / zoom.max(0.01));
+        }
+
+        for &child in children.into_iter().flatten() {
+            let Ok((actor, mut sprite)) = actor_query.get_mut(child) else {
+                continue;
+            };
+            let alpha = sample_fade(&cutscene.sprites[actor.0].fade, elapsed);
+            sprite.color.set_alpha(alpha);
+        }
+
+        if let Ok((mut text, mut color)) = caption_query.get_mut(caption) {
+            let current = cutscene
+                .captions
+                .iter()
+                .find(|caption| (caption.start..caption.end).contains(&elapsed));
+            match current {
+                Some(current) => {
+                    if text.0 != current.text {
+                        text.0.clone_from(&current.text);
+                    }
+                    let fade = ((elapsed - current.start).min(current.end - elapsed)
+                        / CAPTION_FADE_SECS)
+                        .clamp(0.0, 1.0);
+                    color.0.set_alpha(fade);
+                }
+                None => color.0.set_alpha(0.0),
+            }
+        }
+
+        while let Some(cue) = cutscene.sounds.get(player.next_sound)
+            && cue.time <= elapsed
+        {
+            commands.spawn((Name::new("Cutscene Sound"), sound_effect(cue.sound.clone())));
+            player.next_sound += 1;
+        }
+    }
+}
+
+fn set_camera_scale(projection: &mut Projection, scale: f32) {
+    if let Projection::Orthographic(orthographic) = projection {
+        orthographic.scale = scale;
+    }
+}
+
+/// The camera's position and zoom at `time`, easing in and out between keys.
+fn sample_camera(keys: &[CameraKey], time: f32) -> Option<(Vec2, f32)> {
+    let (from, to, t) = segment(keys, time, |key| key.time)?;
+    let t = t * t * (3.0 - 2.0 * t);
+    Some((
+        from.position.lerp(to.position, t),
+        from.zoom.lerp(to.zoom, t),
+    ))
+}
+
+fn sample_fade(keys: &[FadeKey], time: f32) -> f32 {
+    segment(keys, time, |key| key.time)
+        .map_or(1.0, |(from, to, t)| from.alpha.lerp(to.alpha, t))
+        .clamp(0.0, 1.0)
+}
+
+/// The keys before and after `time` and how far between them it is, from 0.0
+/// to 1.0. Before the first key and after the last, that key is held.
+fn segment<K>(keys: &[K], time: f32, key_time: impl Fn(&K) -> f32) -> Option<(&K, &K, f32)> {
+    let next = keys.iter().position(|key| key_time(key) > time);
+    match next {
+        None => keys.last().map(|last| (last, last, 0.0)),
+        Some(0) => Some((&keys[0], &keys[0], 0.0)),
+        Some(index) => {
+            let (from, to) = (&keys[index - 1], &keys[index]);
+            let span = key_time(to) - key_time(from);
+            Some((from, to, ((time - key_time(from)) / span).clamp(0.0, 1.0)))
+        }
+    }
+}
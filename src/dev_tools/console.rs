@@ -0,0 +1,492 @@
+//! An in-game cheat console, opened with F1.
+//!
+//! Each line typed into the console is parsed into a [`ConsoleCommand`] and run
+//! against the world. Tab completes command names and their arguments, and the
+//! up and down arrows browse previous commands. While the console is open, the
+//! game ignores the keyboard, mouse and gamepads.
+//!
+//! Using a cheat marks the run as [cheated](RunStats::cheated). There are no
+//! weapon or wave commands yet, since the game only has one gun and no waves.
+
+use std::str::FromStr;
+
+use bevy::{
+    input::{
+        ButtonState,
+        common_conditions::input_just_pressed,
+        keyboard::{Key, KeyboardInput},
+    },
+    prelude::*,
+};
+
+use crate::{
+    gameplay::{
+        enemy::{Enemy, EnemyKind, enemy},
+        gun::Gun,
+        level::Level,
+        player::{Health, Invulnerable, Player},
+        stats::RunStats,
+    },
+    input::{ActionState, latch_fixed_actions, update_action_state},
+    replay::ReplayPlayback,
+    sprite_atlas::SpriteAtlas,
+    theme::palette::{HUD_BACKGROUND, LABEL_TEXT},
+    time_scale::TimeScale,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<Console>();
+    app.add_systems(Startup, spawn_console);
+    app.add_systems(
+        PreUpdate,
+        block_actions
+            .after(update_action_state)
+            .before(latch_fixed_actions)
+            .run_if(console_is_open.and(not(resource_exists::<ReplayPlayback>))),
+    );
+    app.add_systems(
+        Update,
+        (
+            toggle_console.run_if(input_just_pressed(TOGGLE_KEY)),
+            type_into_console.run_if(console_is_open),
+            update_console_ui.run_if(resource_changed::<Console>),
+        )
+            .chain(),
+    );
+}
+
+const TOGGLE_KEY: KeyCode = KeyCode::F1;
+
+/// How many lines of output the console keeps.
+const MAX_LOG_LINES: usize = 12;
+
+/// How far from the player `spawn` places enemies.
+pub(super) const SPAWN_DISTANCE: f32 = 400.0;
+
+/// The most enemies a single `spawn` places, so a typo can't hang the game.
+const MAX_SPAWN_COUNT: u32 = 500;
+
+/// Every command, with its arguments and what it does, for `help` and completion.
+const COMMANDS: [(&str, &str, &str); 7] = [
+    ("help", "", "List the commands"),
+    ("clear", "", "Clear the console"),
+    ("spawn", "<kind> [count]", "Spawn enemies around the player"),
+    ("god", "", "Toggle taking no damage"),
+    (
+        "give",
+        "<ammo|health>",
+        "Refill the gun or the player's health",
+    ),
+    (
+        "timescale",
+        "<speed>",
+        "Set how fast gameplay runs, 1 being normal",
+    ),
+    ("kill_all", "", "Remove every enemy"),
+];
+
+#[derive(Resource, Default, Debug)]
+struct Console {
+    open: bool,
+    input: String,
+    log: Vec<String>,
+    /// Previously entered lines, oldest first.
+    history: Vec<String>,
+    /// The entry of [`Self::history`] being shown, if browsing it.
+    history_index: Option<usize>,
+}
+
+impl Console {
+    fn print(&mut self, line: impl Into<String>) {
+        self.log.push(line.into());
+        let excess = self.log.len().saturating_sub(MAX_LOG_LINES);
+        self.log.drain(..excess);
+    }
+
+    /// Complete the word being typed, or list the options if there are several.
+    fn complete(&mut self) {
+        let (head, word) = match self.input.rsplit_once(' ') {
+            Some((head, word)) => (Some(head), word),
+            None => (None, self.input.as_str()),
+        };
+        let options: Vec<String> = match head {
+            None => COMMANDS.iter().map(|(name, ..)| name.to_string()).collect(),
+            Some("spawn") => EnemyKind::ALL
+                .iter()
+                .map(|kind| kind.name().to_lowercase())
+                .collect(),
+            Some("give") => Item::ALL
+                .iter()
+                .map(|item| item.name().to_string())
+                .collect(),
+            Some(_) => return,
+        };
+        let matches: Vec<&String> = options
+            .iter()
+            .filter(|option| option.starts_with(word))
+            .collect();
+        let Some(first) = matches.first() else {
+            return;
+        };
+        let common = matches.iter().fold(first.as_str(), |common, option| {
+            let length = common
+                .chars()
+                .zip(option.chars())
+                .take_while(|(a, b)| a == b)
+                .count();
+            &common[..length]
+        });
+        let mut completed = match head {
+            Some(head) => format!("{head} {common}"),
+            None => common.to_string(),
+        };
+        if matches.len() == 1 {
+            completed.push(' ');
+        } else {
+            let options: Vec<&str> = matches.iter().map(|option| option.as_str()).collect();
+            self.print(options.join("  "));
+        }
+        self.input = completed;
+    }
+
+    fn browse_history(&mut self, older: bool) {
+        self.history_index = match (self.history_index, older) {
+            (None, true) => self.history.len().checked_sub(1),
+            (Some(index), true) => Some(index.saturating_sub(1)),
+            (Some(index), false) => Some(index + 1).filter(|&index| index < self.history.len()),
+            (None, false) => None,
+        };
+        self.input = self
+            .history_index
+            .map(|index| self.history[index].clone())
+            .unwrap_or_default();
+    }
+}
+
+/// A parsed line of console input.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ConsoleCommand {
+    Help,
+    Clear,
+    Spawn { kind: EnemyKind, count: u32 },
+    God,
+    Give(Item),
+    TimeScale(f32),
+    KillAll,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Item {
+    Ammo,
+    Health,
+}
+
+impl Item {
+    const ALL: [Self; 2] = [Self::Ammo, Self::Health];
+
+    fn name(self) -> &'static str {
+        match self {
+            Self::Ammo => "ammo",
+            Self::Health => "health",
+        }
+    }
+}
+
+impl FromStr for ConsoleCommand {
+    type Err = String;
+
+    fn from_str(line: &str) -> Result<Self, Self::Err> {
+        let mut words = line.split_whitespace();
+        let name = words.next().unwrap_or_default();
+        let arguments: Vec<&str> = words.collect();
+        let command = match (name, arguments.as_slice()) {
+            ("help", []) => Self::Help,
+            ("clear", []) => Self::Clear,
+            ("spawn", [kind, count @ ..]) if count.len() <= 1 => Self::Spawn {
+                kind: EnemyKind::ALL
+                    .into_iter()
+                    .find(|candidate| candidate.name().to_lowercase().starts_with(kind))
+                    .ok_or_else(|| format!("unknown enemy kind `{kind}`"))?,
+                count: match count {
+                    [count] => match count.parse() {
+                        Ok(count) if (1..=MAX_SPAWN_COUNT).contains(&count) => count,
+                        _ => {
+                            return Err(format!(
+                                "invalid count `{count}`, expected 1 to {MAX_SPAWN_COUNT}"
+                            ));
+                        }
+                    },
+                    _ => 1,
+                },
+            },
+            ("god", []) => Self::God,
+            ("give", [item]) => Self::Give(
+                Item::ALL
+                    .into_iter()
+                    .find(|candidate| candidate.name() == *item)
+                    .ok_or_else(|| format!("unknown item `{item}`"))?,
+            ),
+            ("timescale", [speed]) => match speed.parse() {
+                Ok(speed) if speed > 0.0 => Self::TimeScale(speed),
+                _ => return Err(format!("invalid speed `{speed}`")),
+            },
+            ("kill_all", []) => Self::KillAll,
+            _ => {
+                return Err(
+                    match COMMANDS.iter().find(|(command, ..)| *command == name) {
+                        Some((name, usage, _)) => format!("usage: {name} {usage}"),
+                        None => format!("unknown command `{name}`, try `help`"),
+                    },
+                );
+            }
+        };
+        Ok(command)
+    }
+}
+
+impl ConsoleCommand {
+    /// Run the command and return what to print.
+    fn run(self, world: &mut World) -> String {
+        let result = match self {
+            Self::Help => Some(
+                COMMANDS
+                    .iter()
+                    .map(|(name, usage, description)| format!("{name} {usage} - {description}"))
+                    .collect::<Vec<_>>()
+                    .join("\n"),
+            ),
+            Self::Clear => {
+                world.resource_mut::<Console>().log.clear();
+                return String::new();
+            }
+            Self::Spawn { kind, count } => world.run_system_cached_with(spawn, (kind, count)).ok(),
+            Self::God => world.run_system_cached(toggle_god_mode).ok(),
+            Self::Give(item) => world.run_system_cached_with(give, item).ok(),
+            Self::TimeScale(speed) => world.run_system_cached_with(set_time_scale, speed).ok(),
+            Self::KillAll => world.run_system_cached(kill_all).ok(),
+        };
+        match result {
+            Some(output) => {
+                if self.is_cheat()
+                    && let Some(mut stats) = world.get_resource_mut::<RunStats>()
+                {
+                    stats.cheated = true;
+                }
+                output
+            }
+            // The system's parameters were missing, e.g. there's no player.
+            None => "this only works during a run".to_string(),
+        }
+    }
+
+    fn is_cheat(self) -> bool {
+        !matches!(self, Self::Help | Self::Clear)
+    }
+}
+
+fn spawn(
+    In((kind, count)): In<(EnemyKind, u32)>,
+    mut commands: Commands,
+    sprites: If<Res<SpriteAtlas>>,
+    level: Single<Entity, With<Level>>,
+    player: Single<&Transform, With<Player>>,
+) -> String {
+    let center = player.translation.xy();
+    for index in 0..count {
+        // Spread the enemies evenly around the player.
+        let direction = Vec2::from_angle(std::f32::consts::TAU * index as f32 / count as f32);
+        let enemy = commands
+            .spawn(enemy(&sprites, center + direction * SPAWN_DISTANCE))
+            .insert(Enemy::new(kind))
+            .id();
+        commands.entity(*level).add_child(enemy);
+    }
+    format!("spawned {count} {}", kind.name())
+}
+
+fn toggle_god_mode(
+    mut commands: Commands,
+    player: Single<(Entity, Has<Invulnerable>), With<Player>>,
+) -> String {
+    let (entity, invulnerable) = *player;
+    if invulnerable {
+        commands.entity(entity).remove::<Invulnerable>();
+        "god mode off".to_string()
+    } else {
+        commands.entity(entity).insert(Invulnerable);
+        "god mode on".to_string()
+    }
+}
+
+fn give(
+    In(item): In<Item>,
+    mut health: Single<&mut Health, With<Player>>,
+    mut gun: Single<&mut Gun>,
+) -> String {
+    match item {
+        Item::Ammo => gun.refill(),
+        Item::Health => health.current = health.max,
+    }
+    format!("refilled {}", item.name())
+}
+
+fn set_time_scale(In(speed): In<f32>, mut time_scale: ResMut<TimeScale>) -> String {
+    time_scale.slow_motion = speed;
+    format!("time scale set to {speed}")
+}
+
+fn kill_all(mut commands: Commands, enemies: Query<Entity, With<Enemy>>) -> String {
+    let count = enemies.iter().len();
+    for enemy in &enemies {
+        commands.entity(enemy).despawn();
+    }
+    format!("removed {count} enemies")
+}
+
+fn console_is_open(console: Res<Console>) -> bool {
+    console.open
+}
+
+fn block_actions(mut actions: ResMut<ActionState>) {
+    actions.set(0, 0, None);
+}
+
+fn toggle_console(mut console: ResMut<Console>) {
+    console.open = !console.open;
+}
+
+fn type_into_console(
+    mut commands: Commands,
+    mut console: ResMut<Console>,
+    mut keyboard_input: MessageReader<KeyboardInput>,
+) {
+    for input in keyboard_input.read() {
+        if input.state != ButtonState::Pressed {
+            continue;
+        }
+        match &input.logical_key {
+            Key::Enter => {
+                let line = std::mem::take(&mut console.input);
+                let line = line.trim();
+                console.history_index = None;
+                if line.is_empty() {
+                    continue;
+                }
+                console.print(format!("> {line}"));
+                if console.history.last().is_none_or(|last| last != line) {
+                    console.history.push(line.to_string());
+                }
+                match line.parse::<ConsoleCommand>() {
+                    Ok(command) => commands.queue(move |world: &mut World| {
+                        let output = command.run(world);
+                        if !output.is_empty() {
+                            world.resource_mut::<Console>().print(output);
+                        }
+                    }),
+                    Err(error) => console.print(error),
+                }
+            }
+            Key::Backspace => {
+                console.input.pop();
+            }
+            Key::Tab => console.complete(),
+            Key::ArrowUp => console.browse_history(true),
+            Key::ArrowDown => console.browse_history(false),
+            Key::Escape => console.open = false,
+            _ => {
+                if let Some(text) = &input.text
+                    && input.key_code != TOGGLE_KEY
+                {
+                    console
+                        .input
+                        .extend(text.chars().filter(|c| !c.is_control()));
+                }
+            }
+        }
+    }
+}
+
+#[derive(Component)]
+struct ConsoleRoot;
+
+#[derive(Component)]
+struct ConsoleLog;
+
+#[derive(Component)]
+struct ConsoleInput;
+
+fn spawn_console(mut commands: Commands) {
+    commands.spawn((
+        Name::new("Console"),
+        ConsoleRoot,
+        Node {
+            position_type: PositionType::Absolute,
+            width: percent(100),
+            flex_direction: FlexDirection::Column,
+            padding: UiRect::all(px(8)),
+            row_gap: px(4),
+            ..default()
+        },
+        BackgroundColor(HUD_BACKGROUND.with_alpha(0.9)),
+        GlobalZIndex(3),
+        Visibility::Hidden,
+        children![
+            (
+                Name::new("Console Log"),
+                ConsoleLog,
+                Text::default(),
+                TextFont::from_font_size(16.0),
+                TextColor(LABEL_TEXT),
+            ),
+            (
+                Name::new("Console Input"),
+                ConsoleInput,
+                Text::default(),
+                TextFont::from_font_size(16.0),
+            ),
+        ],
+    ));
+}
+
+fn update_console_ui(
+    console: Res<Console>,
+    mut root: Single<&mut Visibility, With<ConsoleRoot>>,
+    mut log: Single<&mut Text, (With<ConsoleLog>, Without<ConsoleInput>)>,
+    mut input: Single<&mut Text, (With<ConsoleInput>, Without<ConsoleLog>)>,
+) {
+    **root = if console.open {
+        Visibility::Inherited
+    } else {
+        Visibility::Hidden
+    };
+    log.0 = console.log.join("\n");
+    input.0 = format!("> {}_", console.input);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_spawn_counts() {
+        let spawn = |count| ConsoleCommand::Spawn {
+            kind: EnemyKind::Tetrahedron,
+            count,
+        };
+        assert_eq!("spawn tetra".parse(), Ok(spawn(1)));
+        assert_eq!("spawn tetra 12".parse(), Ok(spawn(12)));
+        assert_eq!(
+            format!("spawn tetra {MAX_SPAWN_COUNT}").parse(),
+            Ok(spawn(MAX_SPAWN_COUNT))
+        );
+        let too_many = (MAX_SPAWN_COUNT + 1).to_string();
+        for count in ["0", &too_many, "4000000000", "-1", "many"] {
+            assert!(
+                format!("spawn tetra {count}")
+                    .parse::<ConsoleCommand>()
+                    .is_err(),
+                "`{count}` should be rejected",
+            );
+        }
+    }
+}
@@ -0,0 +1,423 @@
+//! A reflection-based entity inspector for tuning gameplay live, toggled with F3.
+//!
+//! The inspector lists named entities that have one of the game's own
+//! components, and shows the numeric fields of the selected entity's reflected
+//! components, with buttons to nudge them while the game runs. The list only
+//! changes when the inspector is opened or refreshed with F4, so short-lived
+//! entities like particles don't shuffle it while clicking through it.
+//!
+//! Nudging a field marks the run as [cheated](RunStats::cheated), since the
+//! edit isn't part of the run's replay.
+
+use std::{any::TypeId, cmp::Reverse};
+
+use bevy::{
+    ecs::query::SpawnDetails, input::common_conditions::input_just_pressed, prelude::*,
+    reflect::ReflectRef,
+};
+
+use crate::{
+    gameplay::stats::RunStats,
+    theme::{palette::HUD_BACKGROUND, prelude::*},
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<Inspector>();
+    app.add_systems(Startup, spawn_inspector);
+    app.add_systems(
+        Update,
+        (
+            toggle_inspector.run_if(input_just_pressed(TOGGLE_KEY)),
+            (
+                refresh_entity_list
+                    .run_if(input_just_pressed(TOGGLE_KEY).or(input_just_pressed(REFRESH_KEY))),
+                rebuild_fields.run_if(selection_changed),
+                update_field_labels,
+            )
+                .chain()
+                .run_if(inspector_is_open),
+        )
+            .chain(),
+    );
+}
+
+const TOGGLE_KEY: KeyCode = KeyCode::F3;
+const REFRESH_KEY: KeyCode = KeyCode::F4;
+
+/// How many entities the list shows at most.
+const MAX_LISTED_ENTITIES: usize = 16;
+
+/// How deep to look for numeric fields inside a component, e.g. two levels for
+/// `Transform.translation.x`.
+const MAX_FIELD_DEPTH: usize = 2;
+
+#[derive(Resource, Default, Debug)]
+struct Inspector {
+    open: bool,
+    selected: Option<Entity>,
+    /// The entities currently in the list.
+    listed: Vec<Entity>,
+}
+
+/// A numeric field of a component on an entity.
+#[derive(Clone, Debug)]
+struct Field {
+    entity: Entity,
+    component: TypeId,
+    /// The field's path within the component, e.g. `.translation.x`.
+    path: String,
+}
+
+impl Field {
+    fn value(&self, world: &World) -> Option<f64> {
+        let reflect_component = reflect_component(world, self.component)?;
+        let component = reflect_component.reflect(world.get_entity(self.entity).ok()?)?;
+        let field = component.reflect_path(self.path.as_str()).ok()?;
+        number(field)
+    }
+
+    /// Nudge the value by a tenth of itself, or by one for integers, and mark
+    /// the run as cheated.
+    fn nudge(&self, world: &mut World, up: bool) {
+        let Some(reflect_component) = reflect_component(world, self.component) else {
+            return;
+        };
+        let Ok(entity) = world.get_entity_mut(self.entity) else {
+            return;
+        };
+        let Some(mut component) = reflect_component.reflect_mut(entity) else {
+            return;
+        };
+        let Ok(field) = (*component).reflect_path_mut(self.path.as_str()) else {
+            return;
+        };
+        nudge(field, up);
+        if let Some(mut stats) = world.get_resource_mut::<RunStats>() {
+            stats.cheated = true;
+        }
+    }
+}
+
+fn reflect_component(world: &World, component: TypeId) -> Option<ReflectComponent> {
+    world
+        .resource::<AppTypeRegistry>()
+        .read()
+        .get_type_data::<ReflectComponent>(component)
+        .cloned()
+}
+
+/// Whether a component is one of the game's own.
+fn is_game_component(type_path: &str) -> bool {
+    type_path.starts_with(concat!(env!("CARGO_CRATE_NAME"), "::"))
+}
+
+/// Whether a component's fields are shown: the game's own components, and the
+/// [`Transform`] of entities that have one.
+fn is_inspectable(type_path: &str) -> bool {
+    is_game_component(type_path) || type_path == Transform::type_path()
+}
+
+/// The inspectable components of `entity`, with their short names. Entities
+/// without any of the game's own components have none.
+fn inspectable_components(world: &World, entity: Entity) -> Vec<(TypeId, &'static str)> {
+    let Ok(entity) = world.get_entity(entity) else {
+        return Vec::new();
+    };
+    let registry = world.resource::<AppTypeRegistry>().read();
+    let mut components: Vec<(TypeId, &'static str)> = entity
+        .archetype()
+        .components()
+        .iter()
+        .filter_map(|&id| world.components().get_info(id)?.type_id())
+        .filter_map(|type_id| registry.get(type_id))
+        .filter(|registration| {
+            registration.data::<ReflectComponent>().is_some()
+                && is_inspectable(registration.type_info().type_path())
+        })
+        .map(|registration| {
+            (
+                registration.type_id(),
+                registration.type_info().type_path_table().short_path(),
+            )
+        })
+        .collect();
+    if !components.iter().any(|&(type_id, _)| {
+        registry
+            .get(type_id)
+            .is_some_and(|registration| is_game_component(registration.type_info().type_path()))
+    }) {
+        return Vec::new();
+    }
+    components.sort_by_key(|&(_, name)| name);
+    components
+}
+
+/// The paths of the numeric fields in `value`, down to [`MAX_FIELD_DEPTH`].
+fn numeric_fields(value: &dyn PartialReflect, path: String, depth: usize, paths: &mut Vec<String>) {
+    if number(value).is_some() {
+        paths.push(path);
+        return;
+    }
+    if depth >= MAX_FIELD_DEPTH {
+        return;
+    }
+    match value.reflect_ref() {
+        ReflectRef::Struct(value) => {
+            for index in 0..value.field_len() {
+                if let (Some(name), Some(field)) = (value.name_at(index), value.field_at(index)) {
+                    numeric_fields(field, format!("{path}.{name}"), depth + 1, paths);
+                }
+            }
+        }
+        ReflectRef::TupleStruct(value) => {
+            for (index, field) in value.iter_fields().enumerate() {
+                numeric_fields(field, format!("{path}.{index}"), depth + 1, paths);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn number(value: &dyn PartialReflect) -> Option<f64> {
+    if let Some(&value) = value.try_downcast_ref::<f32>() {
+        Some(f64::from(value))
+    } else if let Some(&value) = value.try_downcast_ref::<f64>() {
+        Some(value)
+    } else if let Some(&value) = value.try_downcast_ref::<u32>() {
+        Some(f64::from(value))
+    } else if let Some(&value) = value.try_downcast_ref::<i32>() {
+        Some(f64::from(value))
+    } else {
+        value.try_downcast_ref::<usize>().map(|&value| value as f64)
+    }
+}
+
+fn nudge(value: &mut dyn PartialReflect, up: bool) {
+    let sign: f64 = if up { 1.0 } else { -1.0 };
+    if let Some(value) = value.try_downcast_mut::<f32>() {
+        *value += sign as f32 * (value.abs() * 0.1).max(0.1);
+    } else if let Some(value) = value.try_downcast_mut::<f64>() {
+        *value += sign * (value.abs() * 0.1).max(0.1);
+    } else if let Some(value) = value.try_downcast_mut::<u32>() {
+        *value = if up {
+            value.saturating_add(1)
+        } else {
+            value.saturating_sub(1)
+        };
+    } else if let Some(value) = value.try_downcast_mut::<i32>() {
+        *value += if up { 1 } else { -1 };
+    } else if let Some(value) = value.try_downcast_mut::<usize>() {
+        *value = if up {
+            value.saturating_add(1)
+        } else {
+            value.saturating_sub(1)
+        };
+    }
+}
+
+fn inspector_is_open(inspector: Res<Inspector>) -> bool {
+    inspector.open
+}
+
+fn selection_changed(inspector: Res<Inspector>, mut shown: Local<Option<Entity>>) -> bool {
+    let changed = inspector.selected != *shown;
+    *shown = inspector.selected;
+    changed
+}
+
+#[derive(Component)]
+struct InspectorRoot;
+
+#[derive(Component)]
+struct EntityList;
+
+#[derive(Component)]
+struct FieldList;
+
+#[derive(Component)]
+struct FieldLabel(Field);
+
+fn spawn_inspector(mut commands: Commands) {
+    commands.spawn((
+        Name::new("Inspector"),
+        InspectorRoot,
+        Node {
+            position_type: PositionType::Absolute,
+            left: px(8),
+            top: px(8),
+            max_height: percent(95),
+            padding: UiRect::all(px(8)),
+            column_gap: px(16),
+            overflow: Overflow::clip(),
+            ..default()
+        },
+        BackgroundColor(HUD_BACKGROUND.with_alpha(0.9)),
+        GlobalZIndex(3),
+        Visibility::Hidden,
+        children![
+            (
+                Name::new("Entity List"),
+                EntityList,
+                Node {
+                    flex_direction: FlexDirection::Column,
+                    row_gap: px(4),
+                    ..default()
+                },
+            ),
+            (
+                Name::new("Field List"),
+                FieldList,
+                Node {
+                    display: Display::Grid,
+                    grid_template_columns: vec![
+                        GridTrack::auto(),
+                        GridTrack::px(30.0),
+                        GridTrack::px(100.0),
+                        GridTrack::px(30.0),
+                    ],
+                    column_gap: px(8),
+                    row_gap: px(4),
+                    align_items: AlignItems::Center,
+                    ..default()
+                },
+            ),
+        ],
+    ));
+}
+
+fn toggle_inspector(
+    mut inspector: ResMut<Inspector>,
+    mut root: Single<&mut Visibility, With<InspectorRoot>>,
+) {
+    inspector.open = !inspector.open;
+    **root = if inspector.open {
+        Visibility::Inherited
+    } else {
+        Visibility::Hidden
+    };
+}
+
+/// Rebuild the entity list from the inspectable entities. The oldest entities
+/// are kept when there are too many, so the level and the player aren't pushed
+/// out by short-lived effects.
+fn refresh_entity_list(world: &mut World) {
+    let this_run = world.change_tick();
+    let mut named = world.query_filtered::<(Entity, &Name, SpawnDetails), Without<Node>>();
+    let mut entities: Vec<(Entity, String, u32)> = named
+        .iter(world)
+        .filter(|&(entity, ..)| !inspectable_components(world, entity).is_empty())
+        .map(|(entity, name, spawn)| {
+            let age = this_run.get().wrapping_sub(spawn.spawn_tick().get());
+            (entity, name.to_string(), age)
+        })
+        .collect();
+    entities.sort_by_key(|&(.., age)| Reverse(age));
+    entities.truncate(MAX_LISTED_ENTITIES);
+    entities.sort_by(|a, b| a.1.cmp(&b.1).then(a.0.cmp(&b.0)));
+
+    let listed: Vec<Entity> = entities.iter().map(|&(entity, ..)| entity).collect();
+    let inspector = world.resource::<Inspector>();
+    if listed == inspector.listed {
+        return;
+    }
+    let selected_is_gone = inspector
+        .selected
+        .is_some_and(|selected| world.get_entity(selected).is_err());
+    let mut inspector = world.resource_mut::<Inspector>();
+    inspector.listed = listed;
+    if selected_is_gone {
+        inspector.selected = None;
+    }
+
+    let mut list = world.query_filtered::<Entity, With<EntityList>>();
+    let Ok(list) = list.single(world) else {
+        return;
+    };
+    world
+        .entity_mut(list)
+        .despawn_children()
+        .with_children(|parent| {
+            parent.spawn(widget::label("Entities (F4 to refresh)"));
+            for (entity, name, _) in entities {
+                parent.spawn(widget::button_medium(
+                    format!("{name} {entity}"),
+                    move |_: On<Pointer<Click>>, mut inspector: ResMut<Inspector>| {
+                        inspector.selected = Some(entity);
+                    },
+                ));
+            }
+        });
+}
+
+/// Show the fields of the selected entity.
+fn rebuild_fields(world: &mut World) {
+    let mut rows = Vec::new();
+    if let Some(entity) = world.resource::<Inspector>().selected {
+        for (component, name) in inspectable_components(world, entity) {
+            let Some(reflect_component) = reflect_component(world, component) else {
+                continue;
+            };
+            let Some(value) = reflect_component.reflect(world.entity(entity)) else {
+                continue;
+            };
+            let mut paths = Vec::new();
+            numeric_fields(value.as_partial_reflect(), String::new(), 0, &mut paths);
+            rows.extend(paths.into_iter().map(|path| {
+                let label = format!("{name}{path}");
+                (
+                    label,
+                    Field {
+                        entity,
+                        component,
+                        path,
+                    },
+                )
+            }));
+        }
+    }
+
+    let mut list = world.query_filtered::<Entity, With<FieldList>>();
+    let Ok(list) = list.single(world) else {
+        return;
+    };
+    world
+        .entity_mut(list)
+        .despawn_children()
+        .with_children(|parent| {
+            for (label, field) in rows {
+                parent.spawn(widget::label(label));
+                parent.spawn(nudge_button("-", field.clone(), false));
+                parent.spawn((widget::label(""), FieldLabel(field.clone())));
+                parent.spawn(nudge_button("+", field, true));
+            }
+        });
+}
+
+fn nudge_button(text: &'static str, field: Field, up: bool) -> impl Bundle {
+    widget::button_small(
+        text,
+        move |_: On<Pointer<Click>>, mut commands: Commands| {
+            let field = field.clone();
+            commands.queue(move |world: &mut World| field.nudge(world, up));
+        },
+    )
+}
+
+fn update_field_labels(world: &mut World) {
+    let mut labels = world.query::<(Entity, &FieldLabel)>();
+    let values: Vec<(Entity, String)> = labels
+        .iter(world)
+        .map(|(entity, label)| {
+            let value = label
+                .0
+                .value(world)
+                .map_or_else(|| "-".to_string(), |value| format!("{value:.2}"));
+            (entity, value)
+        })
+        .collect();
+    for (entity, value) in values {
+        if let Some(mut text) = world.get_mut::<Text>(entity) {
+            text.0 = value;
+        }
+    }
+}
@@ -1,5 +1,10 @@
 //! Development tools for the game. This plugin is only enabled in dev builds.
 
+mod console;
+mod inspector;
+mod overlay;
+mod time_control;
+
 use bevy::{
     dev_tools::states::log_transitions, input::common_conditions::input_just_pressed, prelude::*,
 };
@@ -7,6 +12,13 @@ use bevy::{
 use crate::screens::Screen;
 
 pub(super) fn plugin(app: &mut App) {
+    app.add_plugins((
+        console::plugin,
+        inspector::plugin,
+        overlay::plugin,
+        time_control::plugin,
+    ));
+
     // Log `Screen` state transitions.
     app.add_systems(Update, log_transitions::<Screen>);
 
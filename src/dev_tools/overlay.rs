@@ -0,0 +1,202 @@
+//! A debug overlay showing what physics and the AI are doing, toggled with F2.
+//!
+//! It draws collider outlines, velocities, the player's movement intent, the
+//! gun's aim, where enemies are heading, the point enemies are spawned at and
+//! the ring the console spawns enemies on, along with a panel of diagnostics.
+
+use avian2d::{
+    diagnostics::{PhysicsTotalDiagnostics, PhysicsTotalDiagnosticsPlugin},
+    prelude::*,
+};
+use bevy::{
+    color::palettes::tailwind,
+    diagnostic::{DiagnosticsStore, FrameTimeDiagnosticsPlugin},
+    input::common_conditions::input_just_pressed,
+    prelude::*,
+};
+
+use crate::{
+    dev_tools::console::SPAWN_DISTANCE,
+    gameplay::{
+        enemy::{Enemy, SPAWN_POINT},
+        gun::{Bullet, Gun},
+        level::Level,
+        movement::MovementController,
+        player::Player,
+    },
+    theme::palette::{HUD_BACKGROUND, LABEL_TEXT},
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_plugins((PhysicsDebugPlugin, PhysicsTotalDiagnosticsPlugin));
+    if !app.is_plugin_added::<FrameTimeDiagnosticsPlugin>() {
+        app.add_plugins(FrameTimeDiagnosticsPlugin::default());
+    }
+    app.insert_gizmo_config(
+        PhysicsGizmos::default(),
+        GizmoConfig {
+            enabled: false,
+            ..default()
+        },
+    );
+
+    app.init_resource::<DebugOverlay>();
+    app.add_systems(Startup, spawn_diagnostics_panel);
+    app.add_systems(
+        Update,
+        (
+            toggle_overlay.run_if(input_just_pressed(TOGGLE_KEY)),
+            (
+                draw_velocities,
+                draw_intents,
+                draw_aim,
+                draw_enemy_targets,
+                draw_spawn_point,
+                draw_spawn_ring,
+                update_diagnostics_panel,
+            )
+                .run_if(overlay_is_enabled),
+        )
+            .chain(),
+    );
+}
+
+const TOGGLE_KEY: KeyCode = KeyCode::F2;
+
+/// How long the arrows showing directions are, in pixels.
+const DIRECTION_LENGTH: f32 = 40.0;
+
+/// How big the cross marking the spawn point is, in pixels.
+const SPAWN_POINT_SIZE: f32 = 12.0;
+
+/// How long the gun's aim line is, in pixels.
+const AIM_LENGTH: f32 = 600.0;
+
+#[derive(Resource, Default, Debug)]
+struct DebugOverlay {
+    enabled: bool,
+}
+
+fn overlay_is_enabled(overlay: Res<DebugOverlay>) -> bool {
+    overlay.enabled
+}
+
+fn toggle_overlay(
+    mut overlay: ResMut<DebugOverlay>,
+    mut config_store: ResMut<GizmoConfigStore>,
+    mut panel: Single<&mut Visibility, With<DiagnosticsPanel>>,
+) {
+    overlay.enabled = !overlay.enabled;
+    config_store.config_mut::<PhysicsGizmos>().0.enabled = overlay.enabled;
+    **panel = if overlay.enabled {
+        Visibility::Inherited
+    } else {
+        Visibility::Hidden
+    };
+}
+
+/// Velocity arrows are a tenth of the distance covered per second.
+fn draw_velocities(mut gizmos: Gizmos, query: Query<(&GlobalTransform, &LinearVelocity)>) {
+    for (transform, velocity) in &query {
+        let start = transform.translation().xy();
+        gizmos.arrow_2d(start, start + velocity.0 * 0.1, tailwind::SKY_400);
+    }
+}
+
+fn draw_intents(mut gizmos: Gizmos, query: Query<(&GlobalTransform, &MovementController)>) {
+    for (transform, controller) in &query {
+        let start = transform.translation().xy();
+        gizmos.arrow_2d(
+            start,
+            start + controller.intent * DIRECTION_LENGTH,
+            tailwind::LIME_400,
+        );
+    }
+}
+
+fn draw_aim(mut gizmos: Gizmos, gun: Single<(&GlobalTransform, &Gun)>) {
+    let (transform, gun) = *gun;
+    let start = transform.translation().xy();
+    let end = start + Vec2::from_angle(gun.angle()) * AIM_LENGTH;
+    gizmos.line_2d(start, end, tailwind::RED_400.with_alpha(0.5));
+}
+
+/// Enemies head straight for the player.
+fn draw_enemy_targets(
+    mut gizmos: Gizmos,
+    enemies: Query<&GlobalTransform, With<Enemy>>,
+    player: Single<&GlobalTransform, With<Player>>,
+) {
+    let target = player.translation().xy();
+    for transform in &enemies {
+        let start = transform.translation().xy();
+        let direction = (target - start).normalize_or_zero();
+        gizmos.arrow_2d(
+            start,
+            start + direction * DIRECTION_LENGTH,
+            tailwind::FUCHSIA_400,
+        );
+    }
+}
+
+fn draw_spawn_point(mut gizmos: Gizmos, level: Single<&GlobalTransform, With<Level>>) {
+    let point = level.transform_point(SPAWN_POINT.extend(0.0)).xy();
+    gizmos.cross_2d(
+        Isometry2d::from_translation(point),
+        SPAWN_POINT_SIZE,
+        tailwind::AMBER_400,
+    );
+}
+
+fn draw_spawn_ring(mut gizmos: Gizmos, player: Single<&GlobalTransform, With<Player>>) {
+    gizmos.circle_2d(
+        Isometry2d::from_translation(player.translation().xy()),
+        SPAWN_DISTANCE,
+        tailwind::AMBER_400.with_alpha(0.5),
+    );
+}
+
+#[derive(Component)]
+struct DiagnosticsPanel;
+
+fn spawn_diagnostics_panel(mut commands: Commands) {
+    commands.spawn((
+        Name::new("Diagnostics Panel"),
+        DiagnosticsPanel,
+        Node {
+            position_type: PositionType::Absolute,
+            right: px(8),
+            bottom: px(8),
+            padding: UiRect::all(px(8)),
+            ..default()
+        },
+        BackgroundColor(HUD_BACKGROUND.with_alpha(0.8)),
+        GlobalZIndex(3),
+        Visibility::Hidden,
+        Pickable::IGNORE,
+        Text::default(),
+        TextFont::from_font_size(16.0),
+        TextColor(LABEL_TEXT),
+    ));
+}
+
+fn update_diagnostics_panel(
+    diagnostics: Res<DiagnosticsStore>,
+    physics: Res<PhysicsTotalDiagnostics>,
+    enemies: Query<(), With<Enemy>>,
+    bullets: Query<(), With<Bullet>>,
+    entities: Query<()>,
+    mut panel: Single<&mut Text, With<DiagnosticsPanel>>,
+) {
+    let fps = diagnostics
+        .get(&FrameTimeDiagnosticsPlugin::FPS)
+        .and_then(|fps| fps.smoothed())
+        .unwrap_or_default();
+    panel.0 = format!(
+        "FPS: {fps:.0}\nEntities: {}\nEnemies: {}\nBullets: {}\nPhysics step: {:.2} ms",
+        entities.iter().len(),
+        enemies.iter().len(),
+        bullets.iter().len(),
+        physics.step_time.as_secs_f64() * 1000.0,
+    );
+}
@@ -0,0 +1,189 @@
+//! Hotkeys for controlling gameplay time while debugging.
+//!
+//! - F5 freezes the simulation without opening the pause menu.
+//! - F6 advances a frozen simulation by exactly one fixed tick.
+//! - F7 and F8 slow gameplay down and speed it up, from 0.1× to 4×.
+//!
+//! Freezing enters [`Pause`], so [`PausableSystems`](crate::PausableSystems)
+//! stop and the UI keeps working, and also pauses virtual time, so no ticks
+//! pile up while frozen. Physics runs on the fixed clock, so it follows the
+//! speed of virtual time.
+//!
+//! Like the console's cheats, using any of these marks the run as
+//! [cheated](RunStats::cheated).
+
+use avian2d::prelude::{Physics, PhysicsTime};
+use bevy::{input::common_conditions::input_just_pressed, prelude::*};
+
+use crate::{
+    Pause,
+    gameplay::stats::RunStats,
+    menus::Menu,
+    screens::Screen,
+    theme::palette::{HUD_BACKGROUND, LABEL_TEXT},
+    time_scale::TimeScale,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<TimeControl>();
+    app.add_systems(Startup, spawn_time_label);
+    app.add_systems(
+        Update,
+        (
+            toggle_freeze.run_if(input_just_pressed(FREEZE_KEY)),
+            step.run_if(input_just_pressed(STEP_KEY).and(is_frozen)),
+            change_speed(false).run_if(input_just_pressed(SLOWER_KEY)),
+            change_speed(true).run_if(input_just_pressed(FASTER_KEY)),
+        )
+            .run_if(in_state(Screen::Gameplay).and(in_state(Menu::None))),
+    );
+    app.add_systems(Update, update_time_label);
+    app.add_systems(FixedLast, end_step.run_if(is_stepping));
+    // Unpausing from anywhere, like the pause menu, also unfreezes.
+    app.add_systems(OnEnter(Pause(false)), unfreeze.run_if(not(is_stepping)));
+}
+
+const FREEZE_KEY: KeyCode = KeyCode::F5;
+const STEP_KEY: KeyCode = KeyCode::F6;
+const SLOWER_KEY: KeyCode = KeyCode::F7;
+const FASTER_KEY: KeyCode = KeyCode::F8;
+
+/// The speeds F7 and F8 step through.
+const SPEEDS: [f32; 7] = [0.1, 0.25, 0.5, 1.0, 1.5, 2.0, 4.0];
+
+#[derive(Resource, Default, Debug)]
+struct TimeControl {
+    frozen: bool,
+    /// Whether a single tick is running, after which the simulation freezes again.
+    stepping: bool,
+}
+
+fn is_frozen(control: Res<TimeControl>) -> bool {
+    control.frozen
+}
+
+fn is_stepping(control: Res<TimeControl>) -> bool {
+    control.stepping
+}
+
+fn toggle_freeze(
+    mut control: ResMut<TimeControl>,
+    mut stats: ResMut<RunStats>,
+    pause: Res<State<Pause>>,
+    mut next_pause: ResMut<NextState<Pause>>,
+    mut physics_time: ResMut<Time<Physics>>,
+    mut virtual_time: ResMut<Time<Virtual>>,
+) {
+    if control.frozen {
+        next_pause.set(Pause(false));
+        physics_time.unpause();
+    } else if !pause.0 {
+        stats.cheated = true;
+        control.frozen = true;
+        next_pause.set(Pause(true));
+        physics_time.pause();
+        virtual_time.pause();
+    }
+}
+
+fn unfreeze(mut control: ResMut<TimeControl>, mut virtual_time: ResMut<Time<Virtual>>) {
+    if control.frozen {
+        control.frozen = false;
+        virtual_time.unpause();
+    }
+}
+
+/// Run the simulation for the next tick. Virtual time stays paused, so giving
+/// the fixed clock exactly one timestep to spend makes exactly one tick happen.
+fn step(
+    mut control: ResMut<TimeControl>,
+    mut stats: ResMut<RunStats>,
+    mut next_pause: ResMut<NextState<Pause>>,
+    mut physics_time: ResMut<Time<Physics>>,
+    mut fixed_time: ResMut<Time<Fixed>>,
+) {
+    stats.cheated = true;
+    control.stepping = true;
+    next_pause.set(Pause(false));
+    physics_time.unpause();
+    let timestep = fixed_time.timestep();
+    fixed_time.accumulate_overstep(timestep);
+}
+
+fn end_step(
+    mut control: ResMut<TimeControl>,
+    mut next_pause: ResMut<NextState<Pause>>,
+    mut physics_time: ResMut<Time<Physics>>,
+) {
+    control.stepping = false;
+    next_pause.set(Pause(true));
+    physics_time.pause();
+}
+
+fn change_speed(faster: bool) -> impl Fn(ResMut<TimeScale>, ResMut<RunStats>) {
+    move |mut time_scale, mut stats| {
+        stats.cheated = true;
+        let current = time_scale.slow_motion;
+        time_scale.slow_motion = if faster {
+            SPEEDS
+                .into_iter()
+                .find(|&speed| speed > current + 0.01)
+                .unwrap_or(SPEEDS[SPEEDS.len() - 1])
+        } else {
+            SPEEDS
+                .into_iter()
+                .rev()
+                .find(|&speed| speed < current - 0.01)
+                .unwrap_or(SPEEDS[0])
+        };
+    }
+}
+
+#[derive(Component)]
+struct TimeLabel;
+
+fn spawn_time_label(mut commands: Commands) {
+    commands.spawn((
+        Name::new("Time Control Label"),
+        TimeLabel,
+        Node {
+            position_type: PositionType::Absolute,
+            top: px(8),
+            justify_self: JustifySelf::Center,
+            padding: UiRect::axes(px(8), px(4)),
+            ..default()
+        },
+        BackgroundColor(HUD_BACKGROUND.with_alpha(0.8)),
+        GlobalZIndex(3),
+        Visibility::Hidden,
+        Pickable::IGNORE,
+        Text::default(),
+        TextFont::from_font_size(16.0),
+        TextColor(LABEL_TEXT),
+    ));
+}
+
+/// Show whether the simulation is frozen or running at an unusual speed.
+fn update_time_label(
+    control: Res<TimeControl>,
+    time_scale: Res<TimeScale>,
+    label: Single<(&mut Text, &mut Visibility), With<TimeLabel>>,
+) {
+    if !control.is_changed() && !time_scale.is_changed() {
+        return;
+    }
+    let (mut text, mut visibility) = label.into_inner();
+    let status = if control.frozen {
+        Some("Frozen (F6 to step)".to_string())
+    } else if time_scale.slow_motion != 1.0 {
+        Some(format!("Speed {}×", time_scale.slow_motion))
+    } else {
+        None
+    };
+    *visibility = if status.is_some() {
+        Visibility::Inherited
+    } else {
+        Visibility::Hidden
+    };
+    text.0 = status.unwrap_or_default();
+}
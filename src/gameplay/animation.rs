@@ -12,6 +12,8 @@ use crate::{
     AppSystems, PausableSystems,
     audio::sound_effect,
     gameplay::{movement::MovementController, player::PlayerAssets},
+    rng::GameRng,
+    sprite_atlas::{SpriteAtlas, SpriteClip},
 };
 
 pub(super) fn plugin(app: &mut App) {
@@ -75,6 +77,7 @@ fn update_animation_atlas(mut query: Query<(&PlayerAnimation, &mut Sprite)>) {
 fn trigger_step_sound_effect(
     mut commands: Commands,
     player_assets: If<Res<PlayerAssets>>,
+    mut rng: ResMut<GameRng>,
     mut step_query: Query<&PlayerAnimation>,
 ) {
     for animation in &mut step_query {
@@ -82,21 +85,26 @@ fn trigger_step_sound_effect(
             && animation.changed()
             && (animation.frame == 2 || animation.frame == 5)
         {
-            let rng = &mut rand::rng();
-            let random_step = player_assets.steps.choose(rng).unwrap().clone();
+            let random_step = player_assets
+                .steps
+                .choose(rng.stream("steps"))
+                .unwrap()
+                .clone();
             commands.spawn(sound_effect(random_step));
         }
     }
 }
 
 /// Component that tracks player's animation state.
-/// It is tightly bound to the texture atlas we use.
+/// Its frames come from the `ducky_idle` and `ducky_walk` clips of the sprite atlas.
 #[derive(Component, Reflect)]
 #[reflect(Component)]
 pub struct PlayerAnimation {
     timer: Timer,
     frame: usize,
     state: PlayerAnimationState,
+    idle: SpriteClip,
+    walking: SpriteClip,
 }
 
 #[derive(Reflect, PartialEq)]
@@ -106,55 +114,40 @@ pub enum PlayerAnimationState {
 }
 
 impl PlayerAnimation {
-    /// The number of idle frames.
-    const IDLE_FRAMES: usize = 2;
-    /// The duration of each idle frame.
-    const IDLE_INTERVAL: Duration = Duration::from_millis(500);
-    /// The number of walking frames.
-    const WALKING_FRAMES: usize = 6;
-    /// The duration of each walking frame.
-    const WALKING_INTERVAL: Duration = Duration::from_millis(50);
-
-    fn idling() -> Self {
+    pub fn new(sprites: &SpriteAtlas) -> Self {
+        let idle = sprites.clip("ducky_idle").clone();
         Self {
-            timer: Timer::new(Self::IDLE_INTERVAL, TimerMode::Repeating),
+            timer: Timer::new(idle.frame_duration, TimerMode::Repeating),
             frame: 0,
             state: PlayerAnimationState::Idling,
+            idle,
+            walking: sprites.clip("ducky_walk").clone(),
         }
     }
 
-    fn walking() -> Self {
-        Self {
-            timer: Timer::new(Self::WALKING_INTERVAL, TimerMode::Repeating),
-            frame: 0,
-            state: PlayerAnimationState::Walking,
+    /// The clip of the current state.
+    fn clip(&self) -> &SpriteClip {
+        match self.state {
+            PlayerAnimationState::Idling => &self.idle,
+            PlayerAnimationState::Walking => &self.walking,
         }
     }
 
-    pub fn new() -> Self {
-        Self::idling()
-    }
-
     /// Update animation timers.
     pub fn update_timer(&mut self, delta: Duration) {
         self.timer.tick(delta);
         if !self.timer.is_finished() {
             return;
         }
-        self.frame = (self.frame + 1)
-            % match self.state {
-                PlayerAnimationState::Idling => Self::IDLE_FRAMES,
-                PlayerAnimationState::Walking => Self::WALKING_FRAMES,
-            };
+        self.frame = (self.frame + 1) % self.clip().frame_count();
     }
 
     /// Update animation state if it changes.
     pub fn update_state(&mut self, state: PlayerAnimationState) {
         if self.state != state {
-            match state {
-                PlayerAnimationState::Idling => *self = Self::idling(),
-                PlayerAnimationState::Walking => *self = Self::walking(),
-            }
+            self.state = state;
+            self.frame = 0;
+            self.timer = Timer::new(self.clip().frame_duration, TimerMode::Repeating);
         }
     }
 
@@ -165,9 +158,6 @@ impl PlayerAnimation {
 
     /// Return sprite index in the atlas.
     pub fn get_atlas_index(&self) -> usize {
-        match self.state {
-            PlayerAnimationState::Idling => self.frame,
-            PlayerAnimationState::Walking => 6 + self.frame,
-        }
+        self.clip().index(self.frame)
     }
 }
@@ -1,111 +1,137 @@
-use std::time::Duration;
+use std::{ops::Range, time::Duration};
 
-use avian2d::prelude::{Collider, LinearVelocity, LockedAxes, Physics, RigidBody};
-use bevy::{
-    image::{ImageLoaderSettings, ImageSampler},
-    prelude::*,
+use avian2d::prelude::{
+    Collider, CollidingEntities, CollisionStart, LinearVelocity, LockedAxes, RigidBody,
+    TransformInterpolation,
 };
+use bevy::prelude::*;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
 
 use crate::{
     AppSystems, PausableSystems,
-    asset_tracking::LoadResource,
-    gameplay::{level::Level, player::Player},
+    asset_tracking::{AssetCollection, AssetGroup, LoadResource},
+    audio::spatial_sound_effect,
+    gameplay::{
+        gun::{Bullet, CRITICAL_MULTIPLIER},
+        level::Level,
+        player::{Health, Invulnerable, Player},
+        stats::RunStats,
+    },
+    input::{Action, FixedActionState},
+    particles::{EmissionMode, ParticleEmitter},
+    rng::GameRng,
+    sprite_atlas::{SpriteAtlas, SpriteClip},
 };
 
 pub(super) fn plugin(app: &mut App) {
-    app.load_resource::<EnemyAssets>();
+    app.load_resource::<EnemyAssets>(AssetGroup::Gameplay);
 
     app.add_systems(
         Update,
         (
             update_enemy_animation_timer.in_set(AppSystems::TickTimers),
-            (update_enemy_atlas).chain().in_set(AppSystems::Update),
+            update_enemy_atlas.in_set(AppSystems::Update),
         )
             .in_set(PausableSystems),
     );
     app.add_systems(
-        Update,
-        spawn_enemy_on_spacebar
-            .in_set(PausableSystems)
-            .in_set(AppSystems::RecordInput),
+        FixedUpdate,
+        (
+            spawn_enemy_on_spacebar.in_set(AppSystems::RecordInput),
+            (follow_player, damage_player_on_contact).in_set(AppSystems::Update),
+        )
+            .in_set(PausableSystems),
     );
 
-    app.add_systems(Update, follow_player);
+    app.add_observer(hit_enemy);
+    app.add_observer(play_enemy_hit_sound);
+    app.add_observer(spawn_enemy_hit_particles);
 }
 
 #[derive(Component, Reflect, Debug)]
 #[reflect(Component)]
-struct Enemy {
+pub struct Enemy {
+    kind: EnemyKind,
     health: f32,
     speed: f32,
+    /// Damage per second dealt to the player while touching it.
+    contact_damage: f32,
+}
+
+/// Which platonic solid an enemy is.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug, Default, Reflect, Serialize, Deserialize)]
+pub enum EnemyKind {
+    #[default]
+    Tetrahedron,
+}
+
+impl EnemyKind {
+    pub const ALL: [Self; 1] = [Self::Tetrahedron];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Tetrahedron => "Tetrahedron",
+        }
+    }
+}
+
+impl Enemy {
+    pub fn new(kind: EnemyKind) -> Self {
+        Self { kind, ..default() }
+    }
+
+    pub fn health(&self) -> f32 {
+        self.health
+    }
 }
 
-#[derive(Resource, Asset, Clone, Reflect)]
+#[derive(Resource, Asset, Clone, Reflect, AssetCollection)]
 #[reflect(Resource)]
-struct EnemyAssets {
+pub struct EnemyAssets {
+    #[dependency]
+    #[asset(path = "audio/sound_effects/hit.wav")]
+    hit: Handle<AudioSource>,
     #[dependency]
-    image: Handle<Image>,
+    #[asset(path = "audio/sound_effects/pop.wav")]
+    pop: Handle<AudioSource>,
 }
 
 impl Default for Enemy {
     fn default() -> Self {
         Self {
+            kind: EnemyKind::default(),
             health: 20.0,
             speed: 300.0,
+            contact_damage: 10.0,
         }
     }
 }
 
-impl FromWorld for EnemyAssets {
-    fn from_world(world: &mut World) -> Self {
-        let assets = world.resource::<AssetServer>();
-        Self {
-            image: assets.load_with_settings(
-                "images/tetra.png",
-                |settings: &mut ImageLoaderSettings| {
-                    // Use `nearest` image sampling to preserve pixel art style.
-                    settings.sampler = ImageSampler::nearest();
-                },
-            ),
-        }
-    }
-}
+/// Where [`Action::SpawnEnemy`] spawns enemies, relative to the level.
+pub const SPAWN_POINT: Vec2 = Vec2::ZERO;
 
 fn spawn_enemy_on_spacebar(
     mut commands: Commands,
-    input: Res<ButtonInput<KeyCode>>,
-    enemy_assets: If<Res<EnemyAssets>>,
-    mut texture_atlas_layouts: ResMut<Assets<TextureAtlasLayout>>,
+    actions: Res<FixedActionState>,
+    sprites: If<Res<SpriteAtlas>>,
     level: Single<Entity, With<Level>>,
 ) {
-    if input.just_pressed(KeyCode::Space) {
-        let enemy = commands
-            .spawn(enemy(&enemy_assets, Vec2::ZERO, &mut texture_atlas_layouts))
-            .id();
+    if actions.just_pressed(Action::SpawnEnemy) {
+        let enemy = commands.spawn(enemy(&sprites, SPAWN_POINT)).id();
         commands.entity(*level).add_child(enemy);
     }
 }
 
-fn enemy(
-    enemy_assets: &EnemyAssets,
-    location: Vec2,
-    texture_atlas_layouts: &mut Assets<TextureAtlasLayout>,
-) -> impl Bundle {
-    let layout = TextureAtlasLayout::from_grid(UVec2::splat(32), 12, 1, None, None);
-    let texture_atlas_layout = texture_atlas_layouts.add(layout);
-    let enemy_animation = EnemyAnimation::new();
+pub fn enemy(sprites: &SpriteAtlas, location: Vec2) -> impl Bundle {
+    let enemy_animation = EnemyAnimation::new(sprites.clip("tetra_spin").clone());
 
     (
         Name::new("Enemy"),
         Enemy::default(),
-        Sprite::from_atlas_image(
-            enemy_assets.image.clone(),
-            TextureAtlas {
-                layout: texture_atlas_layout,
-                index: 0,
-            },
-        ),
+        sprites.sprite("tetra"),
         Transform::from_scale(Vec2::splat(1.0).extend(1.0)).with_translation(location.extend(0.0)),
+        TransformInterpolation,
         enemy_animation,
         Collider::circle(8.0),
         RigidBody::Dynamic,
@@ -118,12 +144,15 @@ fn enemy(
 pub struct EnemyAnimation {
     timer: Timer,
     frame: usize,
+    clip: SpriteClip,
 }
+
 impl EnemyAnimation {
-    pub fn new() -> Self {
+    pub fn new(clip: SpriteClip) -> Self {
         Self {
-            timer: Timer::from_seconds(0.1, TimerMode::Repeating),
+            timer: Timer::new(clip.frame_duration, TimerMode::Repeating),
             frame: 0,
+            clip,
         }
     }
 
@@ -133,7 +162,7 @@ impl EnemyAnimation {
         if !self.timer.is_finished() {
             return;
         }
-        self.frame = (self.frame + 1) % 12;
+        self.frame = (self.frame + 1) % self.clip.frame_count();
     }
 
     /// Whether animation changed this tick.
@@ -149,7 +178,7 @@ fn update_enemy_atlas(mut query: Query<(&EnemyAnimation, &mut Sprite)>) {
             continue;
         };
         if animation.changed() {
-            atlas.index = animation.frame;
+            atlas.index = animation.clip.index(animation.frame);
         }
     }
 }
@@ -164,7 +193,7 @@ fn update_enemy_animation_timer(time: Res<Time>, mut query: Query<&mut EnemyAnim
 fn follow_player(
     enemies: Query<(&mut LinearVelocity, &Transform, &Enemy), Without<Player>>,
     player: Single<&Transform, (With<Player>, Without<Enemy>)>,
-    time: Res<Time<Physics>>,
+    time: Res<Time>,
 ) {
     for (mut velocity, transform, enemy) in enemies {
         let toward_player = (player.translation.xy() - transform.translation.xy()).normalize()
@@ -174,3 +203,136 @@ fn follow_player(
         velocity.0 += toward_player;
     }
 }
+
+/// Triggered on an enemy whenever a bullet hits it, before it is despawned if the
+/// hit was fatal.
+#[derive(EntityEvent, Debug)]
+pub struct EnemyHit {
+    pub entity: Entity,
+    /// Where the enemy was hit, in world space.
+    pub position: Vec2,
+    pub damage: f32,
+    pub critical: bool,
+    /// Whether the hit popped the enemy.
+    pub fatal: bool,
+}
+
+/// Damage an enemy when a bullet hits it, and pop it once its health runs out.
+/// Sounds and particles are left to observers of [`EnemyHit`].
+fn hit_enemy(
+    collision: On<CollisionStart>,
+    mut commands: Commands,
+    mut stats: ResMut<RunStats>,
+    mut rng: ResMut<GameRng>,
+    bullet_query: Query<&Bullet>,
+    mut enemy_query: Query<(&mut Enemy, &GlobalTransform)>,
+) {
+    let Ok(bullet) = bullet_query.get(collision.collider1) else {
+        return;
+    };
+    let Ok((mut enemy, transform)) = enemy_query.get_mut(collision.collider2) else {
+        return;
+    };
+    // Another bullet already popped this enemy in the same physics step.
+    if enemy.health <= 0.0 {
+        return;
+    }
+    commands.entity(collision.collider1).despawn();
+
+    let critical = rng.stream("critical hits").random::<f32>() < bullet.critical_chance;
+    let damage = if critical {
+        bullet.damage * CRITICAL_MULTIPLIER
+    } else {
+        bullet.damage
+    };
+    stats.shots_hit += 1;
+    stats.damage_dealt += damage.min(enemy.health);
+    enemy.health -= damage;
+    let fatal = enemy.health <= 0.0;
+    commands.trigger(EnemyHit {
+        entity: collision.collider2,
+        position: transform.translation().xy(),
+        damage,
+        critical,
+        fatal,
+    });
+    if fatal {
+        *stats.kills.entry(enemy.kind).or_default() += 1;
+        commands.entity(collision.collider2).despawn();
+    }
+}
+
+fn play_enemy_hit_sound(
+    hit: On<EnemyHit>,
+    mut commands: Commands,
+    enemy_assets: If<Res<EnemyAssets>>,
+) {
+    let (name, sound) = if hit.fatal {
+        ("Enemy Pop", &enemy_assets.pop)
+    } else {
+        ("Enemy Hit", &enemy_assets.hit)
+    };
+    commands.spawn((
+        Name::new(name),
+        spatial_sound_effect(sound.clone(), hit.position),
+    ));
+}
+
+fn spawn_enemy_hit_particles(
+    hit: On<EnemyHit>,
+    mut commands: Commands,
+    enemy_query: Query<(&Sprite, &EnemyAnimation)>,
+) {
+    let transform = Transform::from_translation(hit.position.extend(1.0));
+    if !hit.fatal {
+        commands.spawn((Name::new("Bullet Impact"), bullet_impact(), transform));
+    } else if let Ok((sprite, animation)) = enemy_query.get(hit.entity) {
+        commands.spawn((
+            Name::new("Enemy Shatter"),
+            shatter(sprite, animation.clip.frames.clone()),
+            transform,
+        ));
+    }
+}
+
+fn bullet_impact() -> ParticleEmitter {
+    ParticleEmitter {
+        mode: EmissionMode::Burst(6),
+        lifetime: 0.1..0.25,
+        speed: 80.0..200.0,
+        drag: 6.0,
+        end_scale: 0.25,
+        ..default()
+    }
+}
+
+/// Break an enemy into small copies of its own animation frames.
+fn shatter(sprite: &Sprite, frames: Range<usize>) -> ParticleEmitter {
+    ParticleEmitter {
+        mode: EmissionMode::Burst(10),
+        sprite: sprite.clone(),
+        atlas_frames: frames,
+        lifetime: 0.4..0.8,
+        speed: 60.0..220.0,
+        drag: 3.0,
+        start_scale: 0.5,
+        end_scale: 0.1,
+        ..default()
+    }
+}
+
+fn damage_player_on_contact(
+    time: Res<Time>,
+    mut stats: ResMut<RunStats>,
+    enemy_query: Query<&Enemy>,
+    player: Single<(&CollidingEntities, &mut Health), (With<Player>, Without<Invulnerable>)>,
+) {
+    let (colliding, mut health) = player.into_inner();
+    let damage_per_second: f32 = enemy_query
+        .iter_many(colliding.iter())
+        .map(|enemy| enemy.contact_damage)
+        .sum();
+    let damage = (damage_per_second * time.delta_secs()).min(health.current);
+    health.current -= damage;
+    stats.damage_taken += damage;
+}
@@ -0,0 +1,192 @@
+//! Feedback for hitting enemies: floating damage numbers, a hit flash and
+//! hit-stop on critical kills.
+
+use std::time::Duration;
+
+use bevy::prelude::*;
+
+use crate::{
+    AppSystems, PausableSystems, gameplay::enemy::EnemyHit, screens::Screen, settings::Settings,
+    time_scale::TimeScale,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<DamageNumberPool>();
+    app.add_systems(OnExit(Screen::Gameplay), clear_damage_number_pool);
+
+    app.add_observer(spawn_damage_number);
+    app.add_observer(flash_enemy);
+    app.add_observer(hit_stop_on_critical_kill);
+
+    app.add_systems(
+        Update,
+        (
+            (tick_damage_numbers, tick_hit_flashes).in_set(AppSystems::TickTimers),
+            (update_damage_numbers, update_hit_flashes).in_set(AppSystems::Update),
+        )
+            .in_set(PausableSystems),
+    );
+}
+
+/// How long a damage number stays on screen.
+const DAMAGE_NUMBER_LIFETIME: f32 = 0.8;
+/// How fast damage numbers rise, in pixels per second.
+const DAMAGE_NUMBER_RISE_SPEED: f32 = 60.0;
+/// Damage numbers are drawn above everything else in the level.
+const DAMAGE_NUMBER_Z: f32 = 10.0;
+
+/// #fcfbcc
+const DAMAGE_NUMBER_COLOR: Color = Color::srgb(0.988, 0.984, 0.800);
+/// #ffa31a
+const CRITICAL_DAMAGE_NUMBER_COLOR: Color = Color::srgb(1.0, 0.639, 0.102);
+
+/// How long enemies flash after being hit.
+const HIT_FLASH_DURATION: f32 = 0.1;
+/// How bright the sprite tint gets at the start of a flash. Values above 1.0
+/// wash the sprite out towards white.
+const HIT_FLASH_BRIGHTNESS: f32 = 4.0;
+/// The flash brightness used with [`Accessibility::reduce_flashing`](crate::settings::Accessibility).
+const REDUCED_HIT_FLASH_BRIGHTNESS: f32 = 1.5;
+
+/// How long gameplay freezes when a critical hit pops an enemy.
+const HIT_STOP_DURATION: Duration = Duration::from_millis(60);
+
+/// A number rising from where an enemy was hit.
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct DamageNumber {
+    timer: Timer,
+    color: Color,
+}
+
+/// Damage numbers that have faded out, kept around to be reused for the next hit.
+#[derive(Resource, Default)]
+struct DamageNumberPool(Vec<Entity>);
+
+fn clear_damage_number_pool(mut pool: ResMut<DamageNumberPool>) {
+    // The pooled entities are despawned along with the rest of the gameplay screen.
+    pool.0.clear();
+}
+
+fn spawn_damage_number(
+    hit: On<EnemyHit>,
+    mut commands: Commands,
+    settings: Res<Settings>,
+    mut pool: ResMut<DamageNumberPool>,
+) {
+    if !settings.effects.damage_numbers {
+        return;
+    }
+
+    let (text, font_size, color) = if hit.critical {
+        (
+            format!("{:.0}!", hit.damage),
+            28.0,
+            CRITICAL_DAMAGE_NUMBER_COLOR,
+        )
+    } else {
+        (format!("{:.0}", hit.damage), 20.0, DAMAGE_NUMBER_COLOR)
+    };
+    let damage_number = (
+        DamageNumber {
+            timer: Timer::from_seconds(DAMAGE_NUMBER_LIFETIME, TimerMode::Once),
+            color,
+        },
+        Text2d::new(text),
+        TextFont::from_font_size(font_size),
+        TextColor(color),
+        Transform::from_translation(hit.position.extend(DAMAGE_NUMBER_Z)),
+        Visibility::Inherited,
+    );
+
+    match pool.0.pop() {
+        Some(entity) => {
+            commands.entity(entity).insert(damage_number);
+        }
+        None => {
+            commands.spawn((
+                Name::new("Damage Number"),
+                damage_number,
+                DespawnOnExit(Screen::Gameplay),
+            ));
+        }
+    }
+}
+
+fn tick_damage_numbers(time: Res<Time>, mut query: Query<&mut DamageNumber>) {
+    for mut damage_number in &mut query {
+        damage_number.timer.tick(time.delta());
+    }
+}
+
+/// Move damage numbers upwards while fading them out, and return them to the
+/// pool once they are gone.
+fn update_damage_numbers(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut pool: ResMut<DamageNumberPool>,
+    mut query: Query<(Entity, &DamageNumber, &mut Transform, &mut TextColor)>,
+) {
+    for (entity, damage_number, mut transform, mut color) in &mut query {
+        if damage_number.timer.is_finished() {
+            commands
+                .entity(entity)
+                .remove::<DamageNumber>()
+                .insert(Visibility::Hidden);
+            pool.0.push(entity);
+            continue;
+        }
+        transform.translation.y += DAMAGE_NUMBER_RISE_SPEED * time.delta_secs();
+        color.0 = damage_number
+            .color
+            .with_alpha(damage_number.timer.fraction_remaining());
+    }
+}
+
+/// Tints an enemy's [`Sprite`] while it fades back from a hit flash.
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct HitFlash {
+    timer: Timer,
+    brightness: f32,
+}
+
+fn flash_enemy(hit: On<EnemyHit>, mut commands: Commands, settings: Res<Settings>) {
+    if hit.fatal || !settings.effects.hit_flash {
+        return;
+    }
+    let brightness = if settings.accessibility.reduce_flashing {
+        REDUCED_HIT_FLASH_BRIGHTNESS
+    } else {
+        HIT_FLASH_BRIGHTNESS
+    };
+    // Re-inserting restarts the flash if the enemy is already flashing.
+    commands.entity(hit.entity).try_insert(HitFlash {
+        timer: Timer::from_seconds(HIT_FLASH_DURATION, TimerMode::Once),
+        brightness,
+    });
+}
+
+fn tick_hit_flashes(time: Res<Time>, mut query: Query<&mut HitFlash>) {
+    for mut flash in &mut query {
+        flash.timer.tick(time.delta());
+    }
+}
+
+fn update_hit_flashes(mut commands: Commands, mut query: Query<(Entity, &HitFlash, &mut Sprite)>) {
+    for (entity, flash, mut sprite) in &mut query {
+        if flash.timer.is_finished() {
+            sprite.color = Color::WHITE;
+            commands.entity(entity).remove::<HitFlash>();
+            continue;
+        }
+        let brightness = 1.0 + (flash.brightness - 1.0) * flash.timer.fraction_remaining();
+        sprite.color = Color::linear_rgb(brightness, brightness, brightness);
+    }
+}
+
+fn hit_stop_on_critical_kill(hit: On<EnemyHit>, mut time_scale: ResMut<TimeScale>) {
+    if hit.critical && hit.fatal {
+        time_scale.hit_stop(HIT_STOP_DURATION);
+    }
+}
@@ -1,18 +1,25 @@
 use std::f32::consts::PI;
 
-use avian2d::prelude::{Collider, LinearVelocity, LockedAxes, RigidBody};
-use bevy::image::{ImageLoaderSettings, ImageSampler};
+use avian2d::prelude::{
+    Collider, CollisionEventsEnabled, LinearVelocity, LockedAxes, RigidBody, TransformInterpolation,
+};
 use bevy::prelude::*;
-use bevy::window::PrimaryWindow;
 
-use crate::gameplay::level::Level;
-use crate::{AppSystems, PausableSystems, asset_tracking::LoadResource};
+use crate::audio::spatial_sound_effect;
+use crate::gameplay::{level::Level, stats::RunStats};
+use crate::input::{Action, FixedActionState};
+use crate::particles::{EmissionMode, ParticleEmitter};
+use crate::{
+    AppSystems, PausableSystems,
+    asset_tracking::{AssetCollection, AssetGroup, LoadResource},
+    sprite_atlas::SpriteAtlas,
+};
 
 pub(super) fn plugin(app: &mut App) {
-    app.load_resource::<GunAssets>();
+    app.load_resource::<GunAssets>(AssetGroup::Gameplay);
 
     app.add_systems(
-        Update,
+        FixedUpdate,
         (
             update_gun.in_set(PausableSystems),
             shoot_gun
@@ -26,60 +33,70 @@ pub(super) fn plugin(app: &mut App) {
     // .add_systems(FixedUpdate, update_bullet);
 }
 
-#[derive(Resource, Asset, Clone, Reflect)]
+#[derive(Resource, Asset, Clone, Reflect, AssetCollection)]
 #[reflect(Resource)]
 pub struct GunAssets {
     #[dependency]
-    gun: Handle<Image>,
-    #[dependency]
-    bullet: Handle<Image>,
-}
-
-impl FromWorld for GunAssets {
-    fn from_world(world: &mut World) -> Self {
-        let assets = world.resource::<AssetServer>();
-        Self {
-            gun: assets.load_with_settings(
-                "images/gun.png",
-                |settings: &mut ImageLoaderSettings| {
-                    // Use `nearest` image sampling to preserve pixel art style.
-                    settings.sampler = ImageSampler::nearest();
-                },
-            ),
-            bullet: assets.load_with_settings(
-                "images/bullet.png",
-                |settings: &mut ImageLoaderSettings| {
-                    // Use `nearest` image sampling to preserve pixel art style.
-                    settings.sampler = ImageSampler::nearest();
-                },
-            ),
-        }
-    }
+    #[asset(path = "audio/sound_effects/gunshot.wav")]
+    gunshot: Handle<AudioSource>,
 }
 
-pub fn gun_bundle(gun_assets: &GunAssets) -> impl Bundle {
+pub fn gun_bundle(sprites: &SpriteAtlas) -> impl Bundle {
     let mut timer = Timer::from_seconds(1.0 / 3.0, TimerMode::Once);
     timer.finish();
     (
-        Sprite::from_image(gun_assets.gun.clone()),
+        sprites.sprite("gun"),
         Gun {
             ammo: 7,
             max_ammo: 7,
             ..Default::default()
         },
         Transform::from_xyz(32.0, 0.0, 0.0),
+        TransformInterpolation,
     )
 }
 
-#[derive(Component, Default)]
-struct Gun {
+#[derive(Component, Reflect, Default)]
+#[reflect(Component)]
+pub struct Gun {
     state: GunState,
     ammo: u32,
     max_ammo: u32,
     angle: f32,
 }
 
-#[derive(Default, PartialEq, Eq)]
+impl Gun {
+    /// Rounds left in the magazine.
+    pub fn ammo(&self) -> u32 {
+        self.ammo
+    }
+
+    /// The size of the magazine.
+    pub fn max_ammo(&self) -> u32 {
+        self.max_ammo
+    }
+
+    /// The direction the gun is aiming in, in radians.
+    pub fn angle(&self) -> f32 {
+        self.angle
+    }
+
+    /// Fill the magazine, cancelling any reload.
+    pub fn refill(&mut self) {
+        self.ammo = self.max_ammo;
+        self.state = GunState::Ready;
+    }
+
+    /// How far along the current reload is, from 0.0 to 1.0, if reloading.
+    pub fn reload_progress(&self) -> Option<f32> {
+        match &self.state {
+            GunState::Reloading(timer) => Some(timer.fraction()),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Reflect, Default, PartialEq, Eq)]
 enum GunState {
     #[default]
     Ready,
@@ -89,7 +106,7 @@ enum GunState {
 
 fn update_gun(
     mut gun: Single<(&mut Gun, &mut Transform, &mut Sprite)>,
-    window: Single<&Window, With<PrimaryWindow>>,
+    actions: Res<FixedActionState>,
     time: Res<Time>,
 ) {
     let mut extra_rotation = 0.0;
@@ -112,15 +129,14 @@ fn update_gun(
         GunState::Ready => (),
     }
 
-    if let Some(position) = window.cursor_position() {
-        let mouse_vector = position - Vec2::new(window.width() / 2.0, window.height() / 2.0);
-        let angle = Vec2::new(mouse_vector.x, -mouse_vector.y).to_angle();
+    if let Some(aim) = actions.aim() {
+        let angle = aim.to_angle();
         gun.0.angle = angle;
         gun.1.translation = (Vec2::X * 32.0).extend(0.0);
         gun.1.rotation = Quat::default();
         gun.1
             .rotate_around(Vec3::ZERO, Quat::from_rotation_z(angle));
-        if mouse_vector.x.is_sign_positive() {
+        if aim.x.is_sign_positive() {
             gun.2.flip_y = false;
             gun.1.rotate_z(extra_rotation);
         } else {
@@ -132,46 +148,92 @@ fn update_gun(
 
 fn shoot_gun(
     mut commands: Commands,
-    mouse: Res<ButtonInput<MouseButton>>,
+    actions: Res<FixedActionState>,
     gun_assets: If<Res<GunAssets>>,
+    sprites: If<Res<SpriteAtlas>>,
+    mut stats: ResMut<RunStats>,
     gun_query: Query<(&GlobalTransform, &mut Gun)>,
     level: Single<Entity, With<Level>>,
 ) {
     for (transform, mut gun) in gun_query {
-        if mouse.pressed(MouseButton::Left) && gun.state == GunState::Ready && gun.ammo > 0 {
+        if actions.pressed(Action::Fire) && gun.state == GunState::Ready && gun.ammo > 0 {
             let bullet = commands
                 .spawn(bullet_bundle(
-                    &gun_assets,
+                    &sprites,
                     Transform::from_translation(transform.translation())
                         .with_rotation(transform.rotation()),
                     Vec2::from_angle(gun.angle) * 320.0,
                 ))
                 .id();
             commands.entity(*level).add_child(bullet);
+            commands.spawn((
+                Name::new("Muzzle Flash"),
+                muzzle_flash(gun.angle),
+                Transform::from_translation(transform.transform_point(GUN_TIP)),
+            ));
+            commands.spawn((
+                Name::new("Gunshot"),
+                spatial_sound_effect(gun_assets.gunshot.clone(), transform.translation().xy()),
+            ));
 
             gun.state = GunState::Shooting(Timer::from_seconds(0.5, TimerMode::Once));
             gun.ammo -= 1;
+            stats.shots_fired += 1;
         }
     }
 }
 
-fn reload_gun(key: Res<ButtonInput<KeyCode>>, mut gun: Single<&mut Gun>) {
-    if key.pressed(KeyCode::KeyR) && gun.state == GunState::Ready && gun.ammo < gun.max_ammo {
+/// Where bullets leave the gun, relative to the gun.
+const GUN_TIP: Vec3 = Vec3::new(16.0, 0.0, 1.0);
+
+fn muzzle_flash(angle: f32) -> ParticleEmitter {
+    ParticleEmitter {
+        mode: EmissionMode::Burst(10),
+        lifetime: 0.05..0.15,
+        speed: 100.0..300.0,
+        direction: angle,
+        spread: 0.35,
+        drag: 4.0,
+        // #fcfbcc
+        start_color: Color::srgb(0.988, 0.984, 0.800),
+        // #ffa31a
+        end_color: Color::srgba(1.0, 0.639, 0.102, 0.0),
+        start_scale: 1.5,
+        end_scale: 0.5,
+        ..default()
+    }
+}
+
+fn reload_gun(actions: Res<FixedActionState>, mut gun: Single<&mut Gun>) {
+    if actions.pressed(Action::Reload) && gun.state == GunState::Ready && gun.ammo < gun.max_ammo {
         gun.state = GunState::Reloading(Timer::from_seconds(2.0, TimerMode::Once));
     }
 }
 
-#[derive(Component)]
-struct Bullet;
+/// How much more damage a critical hit deals.
+pub const CRITICAL_MULTIPLIER: f32 = 2.0;
 
-fn bullet_bundle(gun_assets: &GunAssets, transform: Transform, velocity: Vec2) -> impl Bundle {
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+pub struct Bullet {
+    pub damage: f32,
+    /// The chance for a hit to be critical, from 0.0 to 1.0.
+    pub critical_chance: f32,
+}
+
+fn bullet_bundle(sprites: &SpriteAtlas, transform: Transform, velocity: Vec2) -> impl Bundle {
     (
-        Sprite::from_image(gun_assets.bullet.clone()),
-        Bullet,
+        sprites.sprite("bullet"),
+        Bullet {
+            damage: 10.0,
+            critical_chance: 0.1,
+        },
         LockedAxes::ROTATION_LOCKED,
         LinearVelocity(velocity),
         transform,
+        TransformInterpolation,
         Collider::circle(8.0),
         RigidBody::Dynamic,
+        CollisionEventsEnabled,
     )
 }
@@ -0,0 +1,160 @@
+//! The in-game HUD showing health, ammo, reload progress, run time and kills.
+
+use bevy::{prelude::*, window::PrimaryWindow};
+
+use crate::{
+    AppSystems, Pause,
+    gameplay::{
+        gun::Gun,
+        player::{Health, Player},
+        stats::RunStats,
+    },
+    screens::Screen,
+    theme::{palette::HEALTH_FILL, prelude::*},
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(OnEnter(Screen::Gameplay), spawn_hud);
+    app.add_systems(OnEnter(Pause(true)), hide_hud);
+    app.add_systems(OnExit(Pause(true)), show_hud);
+    app.add_systems(
+        Update,
+        (
+            update_health_bar,
+            update_ammo_pips,
+            update_reload_indicator,
+            update_run_labels,
+        )
+            .in_set(AppSystems::Update)
+            .run_if(in_state(Screen::Gameplay)),
+    );
+}
+
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct Hud;
+
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct HealthBar;
+
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct AmmoPips;
+
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct ReloadIndicator;
+
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct RunTimeLabel;
+
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct KillsLabel;
+
+/// The radius of the reload indicator next to the cursor.
+const RELOAD_INDICATOR_RADIUS: f32 = 14.0;
+
+fn spawn_hud(mut commands: Commands) {
+    commands.spawn((
+        Name::new("HUD"),
+        Hud,
+        Node {
+            position_type: PositionType::Absolute,
+            width: percent(100),
+            height: percent(100),
+            padding: UiRect::all(px(16)),
+            justify_content: JustifyContent::SpaceBetween,
+            align_items: AlignItems::Start,
+            ..default()
+        },
+        Pickable::IGNORE,
+        DespawnOnExit(Screen::Gameplay),
+        children![
+            (
+                Name::new("Player Status"),
+                Node {
+                    flex_direction: FlexDirection::Column,
+                    row_gap: px(8),
+                    ..default()
+                },
+                children![
+                    (widget::progress_bar(200.0, HEALTH_FILL), HealthBar),
+                    (widget::pips(), AmmoPips),
+                ],
+            ),
+            (
+                Name::new("Run Status"),
+                Node {
+                    flex_direction: FlexDirection::Column,
+                    align_items: AlignItems::End,
+                    ..default()
+                },
+                children![
+                    (widget::label("0:00"), RunTimeLabel),
+                    (widget::label("Kills: 0"), KillsLabel),
+                ],
+            ),
+            (
+                widget::radial_progress(RELOAD_INDICATOR_RADIUS),
+                ReloadIndicator,
+                Visibility::Hidden,
+            ),
+        ],
+    ));
+}
+
+fn hide_hud(mut hud: Single<&mut Visibility, With<Hud>>) {
+    **hud = Visibility::Hidden;
+}
+
+fn show_hud(mut hud: Single<&mut Visibility, With<Hud>>) {
+    **hud = Visibility::Inherited;
+}
+
+fn update_health_bar(
+    player: Single<&Health, With<Player>>,
+    mut bar: Single<&mut ProgressBar, With<HealthBar>>,
+) {
+    bar.set_if_neq(ProgressBar(player.fraction()));
+}
+
+fn update_ammo_pips(gun: Single<&Gun>, mut pips: Single<&mut Pips, With<AmmoPips>>) {
+    pips.set_if_neq(Pips {
+        filled: gun.ammo() as usize,
+        total: gun.max_ammo() as usize,
+    });
+}
+
+fn update_reload_indicator(
+    gun: Single<&Gun>,
+    window: Single<&Window, With<PrimaryWindow>>,
+    indicator: Single<(&mut Node, &mut Visibility, &mut RadialProgress), With<ReloadIndicator>>,
+) {
+    let (mut node, mut visibility, mut progress) = indicator.into_inner();
+    let (Some(reload), Some(cursor)) = (gun.reload_progress(), window.cursor_position()) else {
+        visibility.set_if_neq(Visibility::Hidden);
+        return;
+    };
+    // Stay inside the HUD's visibility, so the indicator is hidden while paused.
+    visibility.set_if_neq(Visibility::Inherited);
+    node.position_type = PositionType::Absolute;
+    node.left = px(cursor.x + RELOAD_INDICATOR_RADIUS);
+    node.top = px(cursor.y + RELOAD_INDICATOR_RADIUS);
+    progress.set_if_neq(RadialProgress(reload));
+}
+
+fn update_run_labels(
+    stats: Res<RunStats>,
+    mut time_label: Single<&mut Text, (With<RunTimeLabel>, Without<KillsLabel>)>,
+    mut kills_label: Single<&mut Text, (With<KillsLabel>, Without<RunTimeLabel>)>,
+) {
+    if !stats.is_changed() {
+        return;
+    }
+    let seconds = stats.time.as_secs();
+    time_label.0 = format!("{}:{:02}", seconds / 60, seconds % 60);
+    kills_label.0 = format!("Kills: {}", stats.total_kills());
+}
@@ -3,38 +3,24 @@
 use bevy::prelude::*;
 
 use crate::{
-    asset_tracking::LoadResource,
-    audio::music,
-    gameplay::{
-        gun::GunAssets,
-        player::{PlayerAssets, player},
-    },
+    asset_tracking::{AssetCollection, AssetGroup, LoadResource},
+    gameplay::player::player,
     screens::Screen,
+    sprite_atlas::SpriteAtlas,
 };
 
 pub(super) fn plugin(app: &mut App) {
-    app.load_resource::<LevelAssets>();
+    app.load_resource::<LevelAssets>(AssetGroup::Gameplay);
 }
 
-#[derive(Resource, Asset, Clone, Reflect)]
+#[derive(Resource, Asset, Clone, Reflect, AssetCollection)]
 #[reflect(Resource)]
 pub struct LevelAssets {
     #[dependency]
-    music: Handle<AudioSource>,
-    #[dependency]
+    #[asset(path = "images/splash.png")]
     background: Handle<Image>,
 }
 
-impl FromWorld for LevelAssets {
-    fn from_world(world: &mut World) -> Self {
-        let assets = world.resource::<AssetServer>();
-        Self {
-            music: assets.load("audio/music/Fluffing A Duck.ogg"),
-            background: assets.load("images/splash.png"),
-        }
-    }
-}
-
 pub fn background_bundle(level_assets: Res<LevelAssets>) -> impl Bundle {
     (
         Sprite::from_image(level_assets.background.clone()),
@@ -49,9 +35,7 @@ pub struct Level;
 pub fn spawn_level(
     mut commands: Commands,
     level_assets: Res<LevelAssets>,
-    player_assets: Res<PlayerAssets>,
-    gun_assets: Res<GunAssets>,
-    mut texture_atlas_layouts: ResMut<Assets<TextureAtlasLayout>>,
+    sprites: Res<SpriteAtlas>,
 ) {
     commands.spawn((
         Name::new("Level"),
@@ -59,18 +43,6 @@ pub fn spawn_level(
         Transform::default(),
         Visibility::default(),
         DespawnOnExit(Screen::Gameplay),
-        children![
-            (
-                Name::new("Gameplay Music"),
-                music(level_assets.music.clone())
-            ),
-            background_bundle(level_assets),
-            player(
-                100.0,
-                &player_assets,
-                &gun_assets,
-                &mut texture_atlas_layouts
-            ),
-        ],
+        children![background_bundle(level_assets), player(100.0, &sprites),],
     ));
 }
@@ -7,11 +7,14 @@ use bevy::prelude::*;
 
 mod animation;
 mod camera;
-mod enemy;
+pub mod enemy;
+mod feedback;
 pub mod gun;
+mod hud;
 pub mod level;
-mod movement;
+pub mod movement;
 pub mod player;
+pub mod stats;
 
 pub(super) fn plugin(app: &mut App) {
     app.add_plugins((
@@ -22,5 +25,8 @@ pub(super) fn plugin(app: &mut App) {
         camera::plugin,
         gun::plugin,
         enemy::plugin,
+        feedback::plugin,
+        stats::plugin,
+        hud::plugin,
     ));
 }
@@ -7,19 +7,21 @@
 //!   This is done in the `player` module, as it is specific to the player
 //!   character.
 //! - Apply movement based on [`MovementController`] intent and maximum speed.
-//! - Wrap the character within the window.
+//! - Wrap the character around the edges of the [`WRAP_AREA`].
 //!
-//! Note that the implementation used here is limited for demonstration
-//! purposes. If you want to move the player in a smoother way,
-//! consider using a [fixed timestep](https://github.com/bevyengine/bevy/blob/main/examples/movement/physics_in_fixed_timestep.rs).
+//! Movement happens in [`FixedUpdate`], like the rest of the simulation, and is
+//! smoothed out between ticks by
+//! [`TransformInterpolation`](avian2d::prelude::TransformInterpolation).
+//! See the [fixed timestep example](https://github.com/bevyengine/bevy/blob/main/examples/movement/physics_in_fixed_timestep.rs).
 
-use bevy::{prelude::*, window::PrimaryWindow};
+use bevy::prelude::*;
+use bevy_transform_interpolation::TranslationEasingState;
 
 use crate::{AppSystems, PausableSystems};
 
 pub(super) fn plugin(app: &mut App) {
     app.add_systems(
-        Update,
+        FixedUpdate,
         (apply_movement, apply_screen_wrap)
             .chain()
             .in_set(AppSystems::Update)
@@ -60,19 +62,31 @@ fn apply_movement(
     }
 }
 
+/// The size of the area [`ScreenWrap`] wraps around, centered on the origin: the
+/// default window size, with a margin so characters leave the screen fully
+/// before wrapping. It's fixed rather than following the window, so a run plays
+/// out the same in any window size, in replays and headless.
+pub const WRAP_AREA: Vec2 = Vec2::new(1280.0 + 256.0, 720.0 + 256.0);
+
 #[derive(Component, Reflect)]
 #[reflect(Component)]
 pub struct ScreenWrap;
 
 fn apply_screen_wrap(
-    window: Single<&Window, With<PrimaryWindow>>,
-    mut wrap_query: Query<&mut Transform, With<ScreenWrap>>,
+    mut wrap_query: Query<(&mut Transform, Option<&mut TranslationEasingState>), With<ScreenWrap>>,
 ) {
-    let size = window.size() + 256.0;
+    let size = WRAP_AREA;
     let half_size = size / 2.0;
-    for mut transform in &mut wrap_query {
+    for (mut transform, easing) in &mut wrap_query {
         let position = transform.translation.xy();
         let wrapped = (position + half_size).rem_euclid(size) - half_size;
+        if wrapped == position {
+            continue;
+        }
         transform.translation = wrapped.extend(transform.translation.z);
+        // Teleport instead of sliding across the screen.
+        if let Some(mut easing) = easing {
+            easing.start = Some(transform.translation);
+        }
     }
 }
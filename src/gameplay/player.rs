@@ -1,26 +1,26 @@
 //! Player-specific behavior.
 
-use bevy::{
-    image::{ImageLoaderSettings, ImageSampler},
-    prelude::*,
-};
+use avian2d::prelude::{Collider, CollidingEntities, RigidBody, TransformInterpolation};
+use bevy::prelude::*;
 
 use crate::{
     AppSystems, PausableSystems,
-    asset_tracking::LoadResource,
+    asset_tracking::{AssetCollection, AssetGroup, LoadResource},
     gameplay::{
         animation::PlayerAnimation,
-        gun::{GunAssets, gun_bundle},
+        gun::gun_bundle,
         movement::{MovementController, ScreenWrap},
     },
+    input::{Action, FixedActionState},
+    sprite_atlas::SpriteAtlas,
 };
 
 pub(super) fn plugin(app: &mut App) {
-    app.load_resource::<PlayerAssets>();
+    app.load_resource::<PlayerAssets>(AssetGroup::Gameplay);
 
     // Record directional input as movement controls.
     app.add_systems(
-        Update,
+        FixedUpdate,
         record_player_directional_input
             .in_set(AppSystems::RecordInput)
             .in_set(PausableSystems),
@@ -28,36 +28,32 @@ pub(super) fn plugin(app: &mut App) {
 }
 
 /// The player character.
-pub fn player(
-    max_speed: f32,
-    player_assets: &PlayerAssets,
-    gun_assets: &GunAssets,
-    texture_atlas_layouts: &mut Assets<TextureAtlasLayout>,
-) -> impl Bundle {
-    // A texture atlas is a way to split a single image into a grid of related images.
-    // You can learn more in this example: https://github.com/bevyengine/bevy/blob/latest/examples/2d/texture_atlas.rs
-    let layout = TextureAtlasLayout::from_grid(UVec2::splat(32), 6, 2, Some(UVec2::splat(1)), None);
-    let texture_atlas_layout = texture_atlas_layouts.add(layout);
-    let player_animation = PlayerAnimation::new();
+pub fn player(max_speed: f32, sprites: &SpriteAtlas) -> impl Bundle {
+    let player_animation = PlayerAnimation::new(sprites);
+    let mut sprite = sprites.sprite("ducky");
+    if let Some(atlas) = sprite.texture_atlas.as_mut() {
+        atlas.index = player_animation.get_atlas_index();
+    }
 
     (
         Name::new("Player"),
         Player,
-        Sprite::from_atlas_image(
-            player_assets.ducky.clone(),
-            TextureAtlas {
-                layout: texture_atlas_layout,
-                index: player_animation.get_atlas_index(),
-            },
-        ),
+        Health::new(100.0),
+        sprite,
         Transform::from_scale(Vec2::splat(1.0).extend(1.0)),
+        TransformInterpolation,
         MovementController {
             max_speed,
             ..default()
         },
         ScreenWrap,
         player_animation,
-        children![gun_bundle(gun_assets)],
+        // The player is moved by its `MovementController`, but still needs a body
+        // so enemies can touch it.
+        RigidBody::Kinematic,
+        Collider::circle(12.0),
+        CollidingEntities::default(),
+        children![gun_bundle(sprites)],
     )
 }
 
@@ -65,22 +61,46 @@ pub fn player(
 #[reflect(Component)]
 pub struct Player;
 
+/// A player that takes no damage.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Default, Reflect)]
+#[reflect(Component)]
+pub struct Invulnerable;
+
+/// Hit points of the player.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Reflect)]
+#[reflect(Component)]
+pub struct Health {
+    pub current: f32,
+    pub max: f32,
+}
+
+impl Health {
+    pub fn new(max: f32) -> Self {
+        Self { current: max, max }
+    }
+
+    /// Remaining health as a fraction of the maximum.
+    pub fn fraction(&self) -> f32 {
+        self.current / self.max
+    }
+}
+
 fn record_player_directional_input(
-    input: Res<ButtonInput<KeyCode>>,
+    actions: Res<FixedActionState>,
     mut controller_query: Query<&mut MovementController, With<Player>>,
 ) {
     // Collect directional input.
     let mut intent = Vec2::ZERO;
-    if input.pressed(KeyCode::KeyW) || input.pressed(KeyCode::ArrowUp) {
+    if actions.pressed(Action::MoveUp) {
         intent.y += 1.0;
     }
-    if input.pressed(KeyCode::KeyS) || input.pressed(KeyCode::ArrowDown) {
+    if actions.pressed(Action::MoveDown) {
         intent.y -= 1.0;
     }
-    if input.pressed(KeyCode::KeyA) || input.pressed(KeyCode::ArrowLeft) {
+    if actions.pressed(Action::MoveLeft) {
         intent.x -= 1.0;
     }
-    if input.pressed(KeyCode::KeyD) || input.pressed(KeyCode::ArrowRight) {
+    if actions.pressed(Action::MoveRight) {
         intent.x += 1.0;
     }
 
@@ -94,32 +114,10 @@ fn record_player_directional_input(
     }
 }
 
-#[derive(Resource, Asset, Clone, Reflect)]
+#[derive(Resource, Asset, Clone, Reflect, AssetCollection)]
 #[reflect(Resource)]
 pub struct PlayerAssets {
     #[dependency]
-    ducky: Handle<Image>,
-    #[dependency]
+    #[asset(glob = "audio/sound_effects/step*.ogg")]
     pub steps: Vec<Handle<AudioSource>>,
 }
-
-impl FromWorld for PlayerAssets {
-    fn from_world(world: &mut World) -> Self {
-        let assets = world.resource::<AssetServer>();
-        Self {
-            ducky: assets.load_with_settings(
-                "images/ducky.png",
-                |settings: &mut ImageLoaderSettings| {
-                    // Use `nearest` image sampling to preserve pixel art style.
-                    settings.sampler = ImageSampler::nearest();
-                },
-            ),
-            steps: vec![
-                assets.load("audio/sound_effects/step1.ogg"),
-                assets.load("audio/sound_effects/step2.ogg"),
-                assets.load("audio/sound_effects/step3.ogg"),
-                assets.load("audio/sound_effects/step4.ogg"),
-            ],
-        }
-    }
-}
@@ -0,0 +1,94 @@
+//! Statistics about the current run.
+
+use std::time::Duration;
+
+use bevy::{platform::collections::HashMap, prelude::*};
+
+use crate::{AppSystems, PausableSystems, gameplay::enemy::EnemyKind, screens::Screen};
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<RunStats>();
+    app.add_systems(OnEnter(Screen::Gameplay), reset_run_stats);
+    app.add_systems(
+        FixedUpdate,
+        tick_run_time
+            .in_set(AppSystems::TickTimers)
+            .in_set(PausableSystems)
+            .run_if(in_state(Screen::Gameplay)),
+    );
+}
+
+/// Statistics about the current run, reset whenever a new one starts.
+#[derive(Resource, Reflect, Debug)]
+#[reflect(Resource)]
+pub struct RunStats {
+    /// How long the player has survived.
+    pub time: Duration,
+    /// Enemies popped, by kind.
+    pub kills: HashMap<EnemyKind, u32>,
+    /// Damage dealt to enemies, not counting overkill.
+    pub damage_dealt: f32,
+    /// Damage the player has taken, not counting overkill.
+    pub damage_taken: f32,
+    pub shots_fired: u32,
+    /// Shots that hit an enemy.
+    pub shots_hit: u32,
+    /// Whether the run was continued from a save, in which case it can't be
+    /// replayed.
+    pub resumed: bool,
+    /// Whether a cheat was used during the run, in which case it can't be
+    /// replayed and doesn't count as a highscore.
+    pub cheated: bool,
+}
+
+impl Default for RunStats {
+    fn default() -> Self {
+        Self {
+            time: Duration::ZERO,
+            kills: HashMap::default(),
+            damage_dealt: 0.0,
+            damage_taken: 0.0,
+            shots_fired: 0,
+            shots_hit: 0,
+            resumed: false,
+            cheated: false,
+        }
+    }
+}
+
+/// Points awarded per enemy popped.
+const POINTS_PER_KILL: u32 = 100;
+/// Points awarded per second survived.
+const POINTS_PER_SECOND: u32 = 5;
+
+impl RunStats {
+    /// Enemies popped, of any kind.
+    pub fn total_kills(&self) -> u32 {
+        self.kills.values().sum()
+    }
+
+    /// The fraction of shots that hit an enemy, from 0.0 to 1.0.
+    pub fn accuracy(&self) -> f32 {
+        if self.shots_fired == 0 {
+            return 0.0;
+        }
+        self.shots_hit as f32 / self.shots_fired as f32
+    }
+
+    /// The score of the run: points for kills, time survived and damage dealt,
+    /// multiplied by up to 2x for accuracy.
+    pub fn score(&self) -> u32 {
+        let points = self.total_kills() * POINTS_PER_KILL
+            + self.time.as_secs() as u32 * POINTS_PER_SECOND
+            + self.damage_dealt as u32;
+        (points as f32 * (1.0 + self.accuracy())) as u32
+    }
+}
+
+fn reset_run_stats(mut stats: ResMut<RunStats>) {
+    *stats = RunStats::default();
+}
+
+fn tick_run_time(time: Res<Time>, mut stats: ResMut<RunStats>) {
+    stats.time += time.delta();
+}
@@ -0,0 +1,248 @@
+//! Running the game without a window, renderer or audio output, for tests and
+//! simulations.
+//!
+//! [`HeadlessPlugin`] sets up just enough of Bevy to load assets and run the
+//! gameplay systems, with every frame lasting exactly [`FRAME_TIME`].
+//! [`Simulation`] drives it: it starts a seeded run, feeds it input and steps it
+//! frame by frame.
+//!
+//! There is no window, so the cursor never aims the gun. Characters still wrap
+//! around the same fixed area as in a window.
+
+use std::{
+    thread,
+    time::{Duration, Instant},
+};
+
+use bevy::{
+    asset::AssetPlugin,
+    audio::{AudioLoader, AudioSource},
+    image::{CompressedImageFormats, ImageLoader, TextureAtlasPlugin},
+    input::InputPlugin,
+    prelude::*,
+    scene::ScenePlugin,
+    state::app::StatesPlugin,
+    time::TimeUpdateStrategy,
+};
+
+use crate::{
+    FIXED_TIMESTEP, GamePlugin,
+    asset_tracking::{AssetGroup, LoadAssetGroup, ResourceHandles},
+    gameplay::{enemy::enemy, level::Level, player::Player},
+    input::{Action, ActionState, latch_fixed_actions, update_action_state},
+    replay::{ReplayPlayback, load_last_replay},
+    rng::NextSeed,
+    screens::Screen,
+    sprite_atlas::SpriteAtlas,
+    storage,
+};
+
+/// How much time passes every frame: exactly one tick of the simulation.
+pub const FRAME_TIME: Duration = FIXED_TIMESTEP;
+
+/// How long to wait for assets to load before giving up.
+const LOAD_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// How many frames a replay may take before giving up: ten minutes of ticks.
+const MAX_REPLAY_FRAMES: u32 = 10 * 60 * 60;
+
+/// The game without a window, renderer or audio output.
+pub struct HeadlessPlugin;
+
+impl Plugin for HeadlessPlugin {
+    fn build(&self, app: &mut App) {
+        // Don't touch the player's settings, highscores, replays or saves.
+        storage::use_memory();
+
+        app.add_plugins((
+            MinimalPlugins,
+            TransformPlugin,
+            InputPlugin,
+            StatesPlugin,
+            AssetPlugin::default(),
+            ScenePlugin,
+            ImagePlugin::default_nearest(),
+            TextureAtlasPlugin,
+        ));
+        // The renderer and audio output normally register these.
+        app.register_asset_loader(ImageLoader::new(CompressedImageFormats::NONE));
+        app.init_asset::<AudioSource>();
+        app.init_asset_loader::<AudioLoader>();
+
+        app.insert_resource(TimeUpdateStrategy::ManualDuration(FRAME_TIME));
+
+        app.add_plugins(GamePlugin);
+
+        app.init_resource::<SimulatedInput>();
+        app.add_systems(
+            PreUpdate,
+            apply_simulated_input
+                .after(update_action_state)
+                .before(latch_fixed_actions),
+        );
+    }
+}
+
+/// The input a [`Simulation`] feeds into [`ActionState`], replacing the keyboard,
+/// mouse and gamepads.
+#[derive(Resource, Default, Debug)]
+struct SimulatedInput {
+    pressed: u16,
+    previous: u16,
+    aim: Option<Vec2>,
+}
+
+fn apply_simulated_input(mut input: ResMut<SimulatedInput>, mut actions: ResMut<ActionState>) {
+    actions.set(input.pressed, input.previous, input.aim);
+    input.previous = input.pressed;
+}
+
+/// A headless run of the game, stepped one frame at a time.
+pub struct Simulation {
+    app: App,
+}
+
+impl Simulation {
+    /// Build the game with [`HeadlessPlugin`], wait for its assets to load and
+    /// start a run with `seed`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if an asset fails to load, or if the assets don't load within
+    /// [`LOAD_TIMEOUT`].
+    pub fn new(seed: u64) -> Self {
+        let mut app = App::new();
+        app.add_plugins(HeadlessPlugin);
+        app.finish();
+        app.cleanup();
+
+        LoadAssetGroup(AssetGroup::Gameplay).apply(app.world_mut());
+
+        // Assets load on other threads, so give them a moment between frames.
+        let start = Instant::now();
+        while !app
+            .world()
+            .resource::<ResourceHandles>()
+            .is_done(AssetGroup::Gameplay)
+        {
+            if let Some(failure) = app
+                .world()
+                .resource::<ResourceHandles>()
+                .failures(AssetGroup::Gameplay)
+                .next()
+            {
+                panic!("failed to load {}: {}", failure.path, failure.error);
+            }
+            assert!(
+                start.elapsed() < LOAD_TIMEOUT,
+                "assets didn't load within {LOAD_TIMEOUT:?}",
+            );
+            app.update();
+            thread::sleep(Duration::from_millis(1));
+        }
+
+        app.world_mut().resource_mut::<NextSeed>().0 = Some(seed);
+        app.world_mut()
+            .resource_mut::<NextState<Screen>>()
+            .set(Screen::Gameplay);
+        app.update();
+
+        Self { app }
+    }
+
+    pub fn world(&self) -> &World {
+        self.app.world()
+    }
+
+    pub fn world_mut(&mut self) -> &mut World {
+        self.app.world_mut()
+    }
+
+    /// Advance the game by `frames` frames.
+    pub fn step(&mut self, frames: u32) {
+        for _ in 0..frames {
+            self.app.update();
+        }
+    }
+
+    /// Advance the game by at least `seconds` seconds.
+    pub fn step_seconds(&mut self, seconds: f32) {
+        self.step((seconds / FRAME_TIME.as_secs_f32()).ceil() as u32);
+    }
+
+    /// Hold `action` down until it's [released](Self::release).
+    pub fn press(&mut self, action: Action) {
+        self.world_mut().resource_mut::<SimulatedInput>().pressed |= action.bit();
+    }
+
+    pub fn release(&mut self, action: Action) {
+        self.world_mut().resource_mut::<SimulatedInput>().pressed &= !action.bit();
+    }
+
+    /// Aim in the direction of `offset` from the player, or stop aiming.
+    pub fn aim(&mut self, offset: Option<Vec2>) {
+        self.world_mut().resource_mut::<SimulatedInput>().aim = offset;
+    }
+
+    /// The current screen.
+    pub fn screen(&self) -> Screen {
+        *self.world().resource::<State<Screen>>().get()
+    }
+
+    /// The player, if the level has been spawned.
+    pub fn player(&mut self) -> Option<Entity> {
+        let mut players = self.world_mut().query_filtered::<Entity, With<Player>>();
+        players.iter(self.world()).next()
+    }
+
+    /// Leave the run for the title screen, which saves its replay.
+    pub fn quit_to_title(&mut self) {
+        self.world_mut()
+            .resource_mut::<NextState<Screen>>()
+            .set(Screen::Title);
+        self.step(1);
+    }
+
+    /// Watch the replay of the last run to the end, and return the first tick
+    /// whose checksum didn't match the recording, if any.
+    ///
+    /// # Panics
+    ///
+    /// Panics if no replay was saved, or if it doesn't finish within
+    /// [`MAX_REPLAY_FRAMES`].
+    pub fn watch_last_replay(&mut self) -> Option<usize> {
+        let replay = load_last_replay().expect("the last run should have saved a replay");
+        self.world_mut()
+            .insert_resource(ReplayPlayback::new(replay));
+
+        let mut desync = None;
+        for _ in 0..MAX_REPLAY_FRAMES {
+            // Playback is stopped on the title screen once the replay ends.
+            let Some(playback) = self.world().get_resource::<ReplayPlayback>() else {
+                return desync;
+            };
+            desync = playback.desync();
+            self.step(1);
+        }
+        panic!("the replay didn't finish within {MAX_REPLAY_FRAMES} frames");
+    }
+
+    /// Spawn an enemy at `position` in the level. Unlike enemies spawned with
+    /// [`Action::SpawnEnemy`], it isn't part of the run's replay.
+    pub fn spawn_enemy(&mut self, position: Vec2) -> Entity {
+        self.world_mut()
+            .run_system_cached_with(spawn_enemy_at, position)
+            .expect("the level should have been spawned")
+    }
+}
+
+fn spawn_enemy_at(
+    In(position): In<Vec2>,
+    mut commands: Commands,
+    sprites: Res<SpriteAtlas>,
+    level: Single<Entity, With<Level>>,
+) -> Entity {
+    let enemy = commands.spawn(enemy(&sprites, position)).id();
+    commands.entity(*level).add_child(enemy);
+    enemy
+}
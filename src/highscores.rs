@@ -0,0 +1,170 @@
+//! The local highscore table.
+//!
+//! Every run that ends in a game over is offered to [`Highscores`], which keeps
+//! the best [`MAX_HIGHSCORES`] of them in [`storage`](crate::storage).
+
+use std::cmp::Reverse;
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    gameplay::stats::RunStats, replay::ReplayPlayback, rng::GameRng, screens::Screen, storage,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.insert_resource(load_highscores());
+    // Watching a replay doesn't count as a run.
+    app.add_systems(
+        OnEnter(Screen::GameOver),
+        record_run.run_if(not(resource_exists::<ReplayPlayback>)),
+    );
+    app.add_systems(
+        Update,
+        save_highscores
+            .run_if(resource_changed::<Highscores>.and(not(resource_added::<Highscores>))),
+    );
+}
+
+/// The storage key highscores are saved under.
+const STORAGE_KEY: &str = "highscores";
+
+/// How many runs the highscore table keeps.
+pub const MAX_HIGHSCORES: usize = 10;
+
+/// The best runs so far, from highest to lowest score.
+#[derive(Resource, Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Highscores {
+    pub entries: Vec<HighscoreEntry>,
+    /// The index of the entry for the most recent run, if it made the table.
+    #[serde(skip)]
+    pub latest: Option<usize>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct HighscoreEntry {
+    pub score: u32,
+    /// How long the player survived, in seconds.
+    pub time: u64,
+    pub kills: u32,
+    pub accuracy: f32,
+    /// When the run ended, in seconds since the Unix epoch.
+    pub date: u64,
+    /// The seed the run was played with, missing from runs saved before runs
+    /// were seeded.
+    #[serde(default)]
+    pub seed: Option<u64>,
+}
+
+impl HighscoreEntry {
+    pub fn from_run(stats: &RunStats, seed: u64) -> Self {
+        Self {
+            score: stats.score(),
+            time: stats.time.as_secs(),
+            kills: stats.total_kills(),
+            accuracy: stats.accuracy(),
+            date: unix_time(),
+            seed: Some(seed),
+        }
+    }
+
+    /// The date the run ended, as `YYYY-MM-DD` in UTC.
+    pub fn date_label(&self) -> String {
+        let (year, month, day) = civil_from_days((self.date / 86_400) as i64);
+        format!("{year:04}-{month:02}-{day:02}")
+    }
+}
+
+impl Highscores {
+    /// Add an entry if it's good enough for the table, returning its rank.
+    pub fn insert(&mut self, entry: HighscoreEntry) -> Option<usize> {
+        let rank = self
+            .entries
+            .iter()
+            .position(|other| entry.score > other.score)
+            .unwrap_or(self.entries.len());
+        if rank >= MAX_HIGHSCORES {
+            return None;
+        }
+        self.entries.insert(rank, entry);
+        self.entries.truncate(MAX_HIGHSCORES);
+        Some(rank)
+    }
+}
+
+fn record_run(stats: Res<RunStats>, rng: Res<GameRng>, mut highscores: ResMut<Highscores>) {
+    if stats.cheated {
+        highscores.latest = None;
+        return;
+    }
+    let rank = highscores.insert(HighscoreEntry::from_run(&stats, rng.seed()));
+    highscores.latest = rank;
+}
+
+/// Load highscores from storage, starting a fresh table if they are missing or
+/// unreadable.
+fn load_highscores() -> Highscores {
+    let contents = match storage::read(STORAGE_KEY) {
+        Ok(Some(contents)) => contents,
+        Ok(None) => return Highscores::default(),
+        Err(error) => {
+            warn!("Failed to read highscores: {error}");
+            return Highscores::default();
+        }
+    };
+    match ron::from_str::<Highscores>(&contents) {
+        Ok(mut highscores) => {
+            highscores.entries.sort_by_key(|entry| Reverse(entry.score));
+            highscores.entries.truncate(MAX_HIGHSCORES);
+            highscores
+        }
+        Err(error) => {
+            warn!("Highscores are corrupt, starting a new table: {error}");
+            Highscores::default()
+        }
+    }
+}
+
+fn save_highscores(highscores: Res<Highscores>) {
+    let contents = match ron::ser::to_string_pretty(&*highscores, default()) {
+        Ok(contents) => contents,
+        Err(error) => {
+            error!("Failed to serialize highscores: {error}");
+            return;
+        }
+    };
+    if let Err(error) = storage::write(STORAGE_KEY, &contents) {
+        warn!("Failed to save highscores: {error}");
+    }
+}
+
+/// The current time, in seconds since the Unix epoch.
+#[cfg(not(target_family = "wasm"))]
+fn unix_time() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_secs())
+}
+
+/// The current time, in seconds since the Unix epoch.
+#[cfg(target_family = "wasm")]
+fn unix_time() -> u64 {
+    // `SystemTime` isn't available on the web.
+    (js_sys::Date::now() / 1000.0) as u64
+}
+
+/// Convert days since the Unix epoch to a `(year, month, day)` date.
+/// See <https://howardhinnant.github.io/date_algorithms.html#civil_from_days>.
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
@@ -0,0 +1,389 @@
+//! Action-based input mapping.
+//!
+//! Gameplay and menu systems never look at raw [`KeyCode`]s, [`MouseButton`]s
+//! or [`GamepadButton`]s directly. Instead, they ask [`ActionState`] whether an
+//! abstract [`Action`] is pressed. The physical inputs behind each action are
+//! stored in the rebindable [`InputMap`] resource.
+//!
+//! Systems in [`FixedUpdate`] use [`FixedActionState`] instead, since the fixed
+//! timestep may tick any number of times in a frame.
+
+use bevy::{
+    input::InputSystems, platform::collections::HashMap, prelude::*, window::PrimaryWindow,
+};
+use serde::{Deserialize, Serialize};
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<InputMap>();
+    app.init_resource::<ActionState>();
+    app.init_resource::<FixedActionState>();
+
+    // Resolve actions right after Bevy has updated its input resources, so every
+    // system in `Update` sees the same state.
+    app.add_systems(
+        PreUpdate,
+        (update_action_state.after(InputSystems), latch_fixed_actions).chain(),
+    );
+    app.add_systems(FixedPreUpdate, tick_fixed_actions);
+}
+
+/// An abstract input action that can be bound to one or more [`Binding`]s.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug, Reflect, Serialize, Deserialize)]
+pub enum Action {
+    MoveUp,
+    MoveDown,
+    MoveLeft,
+    MoveRight,
+    Fire,
+    Reload,
+    Dash,
+    SwitchWeapon,
+    SpawnEnemy,
+    Pause,
+    Back,
+}
+
+impl Action {
+    /// All actions, in the order they are listed in the controls menu.
+    pub const ALL: [Self; 11] = [
+        Self::MoveUp,
+        Self::MoveDown,
+        Self::MoveLeft,
+        Self::MoveRight,
+        Self::Fire,
+        Self::Reload,
+        Self::Dash,
+        Self::SwitchWeapon,
+        Self::SpawnEnemy,
+        Self::Pause,
+        Self::Back,
+    ];
+
+    /// A human-readable name for the action.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::MoveUp => "Move Up",
+            Self::MoveDown => "Move Down",
+            Self::MoveLeft => "Move Left",
+            Self::MoveRight => "Move Right",
+            Self::Fire => "Fire",
+            Self::Reload => "Reload",
+            Self::Dash => "Dash",
+            Self::SwitchWeapon => "Switch Weapon",
+            Self::SpawnEnemy => "Spawn Enemy",
+            Self::Pause => "Pause",
+            Self::Back => "Back",
+        }
+    }
+
+    /// The bit representing the action in [`ActionState::pressed_bits`].
+    pub fn bit(self) -> u16 {
+        let index = Self::ALL
+            .iter()
+            .position(|&action| action == self)
+            .unwrap_or_default();
+        1 << index
+    }
+
+    fn default_bindings(self) -> Vec<Binding> {
+        use Binding::*;
+        match self {
+            Self::MoveUp => vec![
+                Key(KeyCode::KeyW),
+                Key(KeyCode::ArrowUp),
+                Gamepad(GamepadButton::DPadUp),
+            ],
+            Self::MoveDown => vec![
+                Key(KeyCode::KeyS),
+                Key(KeyCode::ArrowDown),
+                Gamepad(GamepadButton::DPadDown),
+            ],
+            Self::MoveLeft => vec![
+                Key(KeyCode::KeyA),
+                Key(KeyCode::ArrowLeft),
+                Gamepad(GamepadButton::DPadLeft),
+            ],
+            Self::MoveRight => vec![
+                Key(KeyCode::KeyD),
+                Key(KeyCode::ArrowRight),
+                Gamepad(GamepadButton::DPadRight),
+            ],
+            Self::Fire => vec![
+                Mouse(MouseButton::Left),
+                Gamepad(GamepadButton::RightTrigger2),
+            ],
+            Self::Reload => vec![Key(KeyCode::KeyR), Gamepad(GamepadButton::West)],
+            Self::Dash => vec![Key(KeyCode::ShiftLeft), Gamepad(GamepadButton::South)],
+            Self::SwitchWeapon => vec![Key(KeyCode::KeyQ), Gamepad(GamepadButton::North)],
+            Self::SpawnEnemy => vec![Key(KeyCode::Space)],
+            Self::Pause => vec![Key(KeyCode::KeyP), Gamepad(GamepadButton::Start)],
+            Self::Back => vec![Key(KeyCode::Escape), Gamepad(GamepadButton::East)],
+        }
+    }
+}
+
+/// A single physical input that can trigger an [`Action`].
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug, Reflect, Serialize, Deserialize)]
+pub enum Binding {
+    Key(KeyCode),
+    Mouse(MouseButton),
+    Gamepad(GamepadButton),
+}
+
+impl Binding {
+    /// A short human-readable name for the binding, e.g. "W" or "Mouse Left".
+    pub fn name(self) -> String {
+        match self {
+            Self::Key(key) => {
+                let name = format!("{key:?}");
+                name.strip_prefix("Key")
+                    .or_else(|| name.strip_prefix("Digit"))
+                    .unwrap_or(&name)
+                    .to_string()
+            }
+            Self::Mouse(MouseButton::Other(button)) => format!("Mouse {button}"),
+            Self::Mouse(button) => format!("Mouse {button:?}"),
+            Self::Gamepad(button) => format!("Pad {button:?}"),
+        }
+    }
+
+    fn pressed(
+        self,
+        keys: &ButtonInput<KeyCode>,
+        mouse: &ButtonInput<MouseButton>,
+        gamepads: &Query<&Gamepad>,
+    ) -> bool {
+        match self {
+            Self::Key(key) => keys.pressed(key),
+            Self::Mouse(button) => mouse.pressed(button),
+            Self::Gamepad(button) => gamepads.iter().any(|gamepad| gamepad.pressed(button)),
+        }
+    }
+}
+
+/// Maps every [`Action`] to the [`Binding`]s that trigger it.
+#[derive(Resource, Clone, PartialEq, Debug, Reflect, Serialize, Deserialize)]
+#[reflect(Resource)]
+pub struct InputMap {
+    bindings: HashMap<Action, Vec<Binding>>,
+}
+
+impl Default for InputMap {
+    fn default() -> Self {
+        Self {
+            bindings: Action::ALL
+                .into_iter()
+                .map(|action| (action, action.default_bindings()))
+                .collect(),
+        }
+    }
+}
+
+impl InputMap {
+    /// The bindings for an action.
+    pub fn bindings(&self, action: Action) -> &[Binding] {
+        self.bindings.get(&action).map_or(&[], Vec::as_slice)
+    }
+
+    /// Give actions missing from the map (e.g. ones added after the map was saved)
+    /// their default bindings.
+    pub fn fill_missing_actions(&mut self) {
+        for action in Action::ALL {
+            self.bindings
+                .entry(action)
+                .or_insert_with(|| action.default_bindings());
+        }
+    }
+
+    /// The action that already uses `binding`, if any, ignoring `except`.
+    pub fn conflict(&self, binding: Binding, except: Action) -> Option<Action> {
+        Action::ALL
+            .into_iter()
+            .filter(|&action| action != except)
+            .find(|&action| self.bindings(action).contains(&binding))
+    }
+
+    /// Bind `binding` to `action` in the given slot, appending it if the slot
+    /// doesn't exist yet.
+    ///
+    /// Returns the conflicting action instead if the binding is already in use.
+    pub fn rebind(&mut self, action: Action, slot: usize, binding: Binding) -> Result<(), Action> {
+        if let Some(conflict) = self.conflict(binding, action) {
+            return Err(conflict);
+        }
+        let bindings = self.bindings.entry(action).or_default();
+        if bindings.contains(&binding) {
+            return Ok(());
+        }
+        if slot < bindings.len() {
+            bindings[slot] = binding;
+        } else {
+            bindings.push(binding);
+        }
+        Ok(())
+    }
+
+    /// Remove the binding in the given slot of an action, if it exists.
+    pub fn unbind(&mut self, action: Action, slot: usize) {
+        if let Some(bindings) = self.bindings.get_mut(&action)
+            && slot < bindings.len()
+        {
+            bindings.remove(slot);
+        }
+    }
+}
+
+/// The current state of every [`Action`], resolved from the [`InputMap`] once per frame.
+#[derive(Resource, Default, Debug)]
+pub struct ActionState {
+    pressed: HashMap<Action, bool>,
+    previous: HashMap<Action, bool>,
+    aim: Option<Vec2>,
+}
+
+impl ActionState {
+    /// Whether any binding of the action is held down.
+    pub fn pressed(&self, action: Action) -> bool {
+        self.pressed.get(&action).copied().unwrap_or_default()
+    }
+
+    /// Whether the action started being pressed this frame.
+    pub fn just_pressed(&self, action: Action) -> bool {
+        self.pressed(action) && !self.previous.get(&action).copied().unwrap_or_default()
+    }
+
+    /// Where the player is aiming, relative to the center of the window with y
+    /// pointing up, or `None` if the cursor is outside the window.
+    pub fn aim(&self) -> Option<Vec2> {
+        self.aim
+    }
+
+    /// Which actions are pressed, as a set of [`Action::bit`]s.
+    pub fn pressed_bits(&self) -> u16 {
+        Action::ALL
+            .into_iter()
+            .filter(|&action| self.pressed(action))
+            .fold(0, |bits, action| bits | action.bit())
+    }
+
+    /// Overwrite the state with input from elsewhere, e.g. a replay. `pressed` and
+    /// `previous` are in the format of [`ActionState::pressed_bits`].
+    pub fn set(&mut self, pressed: u16, previous: u16, aim: Option<Vec2>) {
+        for action in Action::ALL {
+            self.pressed.insert(action, pressed & action.bit() != 0);
+            self.previous.insert(action, previous & action.bit() != 0);
+        }
+        self.aim = aim;
+    }
+}
+
+/// [`ActionState`] as seen from [`FixedUpdate`]. An action held at any point
+/// between two ticks counts as pressed on the next tick, so short presses aren't
+/// lost when a frame has no tick, and nothing is "just pressed" twice when a
+/// frame has several.
+#[derive(Resource, Default, Debug)]
+pub struct FixedActionState {
+    pressed: u16,
+    previous: u16,
+    /// Actions held at some point since the last tick.
+    latched: u16,
+    /// Actions held this frame.
+    held: u16,
+    aim: Option<Vec2>,
+}
+
+impl FixedActionState {
+    /// Whether the action is held down this tick.
+    pub fn pressed(&self, action: Action) -> bool {
+        self.pressed & action.bit() != 0
+    }
+
+    /// Whether the action started being pressed this tick.
+    pub fn just_pressed(&self, action: Action) -> bool {
+        self.pressed(action) && self.previous & action.bit() == 0
+    }
+
+    /// Where the player is aiming, as in [`ActionState::aim`].
+    pub fn aim(&self) -> Option<Vec2> {
+        self.aim
+    }
+
+    /// The actions held this tick and the last, in the format of
+    /// [`ActionState::pressed_bits`].
+    pub fn bits(&self) -> (u16, u16) {
+        (self.pressed, self.previous)
+    }
+
+    /// Replace this tick's input, e.g. with recorded input.
+    pub fn set(&mut self, pressed: u16, previous: u16, aim: Option<Vec2>) {
+        self.pressed = pressed;
+        self.previous = previous;
+        self.aim = aim;
+    }
+}
+
+/// Collect this frame's [`ActionState`] for the next tick. Systems that override
+/// the [`ActionState`], like replays, run before this.
+pub fn latch_fixed_actions(actions: Res<ActionState>, mut fixed: ResMut<FixedActionState>) {
+    let held = actions.pressed_bits();
+    fixed.latched |= held;
+    fixed.held = held;
+    fixed.aim = actions.aim();
+}
+
+pub fn tick_fixed_actions(mut fixed: ResMut<FixedActionState>) {
+    fixed.previous = fixed.pressed;
+    fixed.pressed = fixed.latched;
+    fixed.latched = fixed.held;
+}
+
+pub fn update_action_state(
+    input_map: Res<InputMap>,
+    keys: Res<ButtonInput<KeyCode>>,
+    mouse: Res<ButtonInput<MouseButton>>,
+    gamepads: Query<&Gamepad>,
+    window: Query<&Window, With<PrimaryWindow>>,
+    mut action_state: ResMut<ActionState>,
+) {
+    let action_state = &mut *action_state;
+    action_state.previous.clone_from(&action_state.pressed);
+    for action in Action::ALL {
+        let pressed = input_map
+            .bindings(action)
+            .iter()
+            .any(|binding| binding.pressed(&keys, &mouse, &gamepads));
+        action_state.pressed.insert(action, pressed);
+    }
+    action_state.aim = window.single().ok().and_then(|window| {
+        let offset = window.cursor_position()? - window.size() / 2.0;
+        Some(Vec2::new(offset.x, -offset.y))
+    });
+}
+
+/// Run condition that is active if the given action was just pressed.
+pub fn action_just_pressed(action: Action) -> impl FnMut(Res<ActionState>) -> bool + Clone {
+    move |action_state: Res<ActionState>| action_state.just_pressed(action)
+}
+
+/// The first binding that was just pressed this frame, if any. Used when rebinding.
+pub fn just_pressed_binding(
+    keys: &ButtonInput<KeyCode>,
+    mouse: &ButtonInput<MouseButton>,
+    gamepads: &Query<&Gamepad>,
+) -> Option<Binding> {
+    keys.get_just_pressed()
+        .next()
+        .map(|&key| Binding::Key(key))
+        .or_else(|| {
+            mouse
+                .get_just_pressed()
+                .next()
+                .map(|&button| Binding::Mouse(button))
+        })
+        .or_else(|| {
+            gamepads
+                .iter()
+                .find_map(|gamepad| gamepad.get_just_pressed().next())
+                .map(|&button| Binding::Gamepad(button))
+        })
+}
@@ -0,0 +1,160 @@
+// Support configuring Bevy lints within code.
+#![cfg_attr(bevy_lint, feature(register_tool), register_tool(bevy))]
+
+mod asset_tracking;
+mod audio;
+pub mod cutscene;
+#[cfg(feature = "dev")]
+mod dev_tools;
+pub mod gameplay;
+pub mod headless;
+mod highscores;
+pub mod input;
+mod menus;
+mod particles;
+mod replay;
+pub mod rng;
+mod save;
+pub mod screens;
+mod settings;
+mod sprite_atlas;
+pub mod storage;
+mod theme;
+pub mod time_scale;
+
+use avian2d::prelude::*;
+use std::time::Duration;
+
+use bevy::{asset::AssetMetaCheck, prelude::*};
+
+/// The whole game, in a window.
+pub struct AppPlugin;
+
+impl Plugin for AppPlugin {
+    fn build(&self, app: &mut App) {
+        // Add Bevy plugins.
+        app.add_plugins(
+            DefaultPlugins
+                .set(AssetPlugin {
+                    // Wasm builds will check for meta files (that don't exist) if this isn't set.
+                    // This causes errors and even panics on web build on itch.
+                    // See https://github.com/bevyengine/bevy_github_ci_template/issues/48.
+                    meta_check: AssetMetaCheck::Never,
+                    ..default()
+                })
+                .set(WindowPlugin {
+                    primary_window: Window {
+                        title: "Bevyjam7".to_string(),
+                        fit_canvas_to_parent: true,
+                        ..default()
+                    }
+                    .into(),
+                    ..default()
+                }),
+        );
+
+        app.add_plugins((
+            GamePlugin,
+            #[cfg(feature = "dev")]
+            dev_tools::plugin,
+        ));
+    }
+}
+
+/// The game itself, without the window, renderer and audio output of
+/// [`DefaultPlugins`]. See [`headless`] for running it without them.
+pub struct GamePlugin;
+
+impl Plugin for GamePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins((
+            asset_tracking::plugin,
+            audio::plugin,
+            cutscene::plugin,
+            gameplay::plugin,
+            highscores::plugin,
+            input::plugin,
+            menus::plugin,
+            particles::plugin,
+            replay::plugin,
+            rng::plugin,
+            save::plugin,
+        ));
+        // Tuples of plugins are limited to 16 elements.
+        app.add_plugins((
+            screens::plugin,
+            settings::plugin,
+            sprite_atlas::plugin,
+            theme::plugin,
+            time_scale::plugin,
+        ));
+        app.add_plugins(PhysicsPlugins::default());
+
+        app.insert_resource(Gravity::ZERO);
+
+        // The simulation runs in `FixedUpdate`, so it plays out the same at any frame rate.
+        app.insert_resource(Time::<Fixed>::from_duration(FIXED_TIMESTEP));
+
+        // Order new `AppSystems` variants by adding them here:
+        app.configure_sets(
+            Update,
+            (
+                AppSystems::TickTimers,
+                AppSystems::RecordInput,
+                AppSystems::Update,
+            )
+                .chain(),
+        );
+        app.configure_sets(
+            FixedUpdate,
+            (
+                AppSystems::TickTimers,
+                AppSystems::RecordInput,
+                AppSystems::Update,
+            )
+                .chain(),
+        );
+
+        // Set up the `Pause` state.
+        app.init_state::<Pause>();
+        app.configure_sets(Update, PausableSystems.run_if(in_state(Pause(false))));
+        app.configure_sets(FixedUpdate, PausableSystems.run_if(in_state(Pause(false))));
+
+        // Spawn the main camera.
+        app.add_systems(Startup, spawn_camera);
+    }
+}
+
+/// How much time every tick of the simulation in [`FixedUpdate`] covers.
+pub const FIXED_TIMESTEP: Duration = Duration::from_nanos(1_000_000_000 / 60);
+
+/// High-level groupings of systems for the app in the `Update` and
+/// `FixedUpdate` schedules.
+/// When adding a new variant, make sure to order it in the `configure_sets`
+/// call above.
+#[derive(SystemSet, Debug, Clone, Copy, Eq, PartialEq, Hash, PartialOrd, Ord)]
+pub enum AppSystems {
+    /// Tick timers.
+    TickTimers,
+    /// Record player input.
+    RecordInput,
+    /// Do everything else (consider splitting this into further variants).
+    Update,
+}
+
+/// Whether or not the game is paused.
+#[derive(States, Copy, Clone, Eq, PartialEq, Hash, Debug, Default)]
+pub struct Pause(pub bool);
+
+/// A system set for systems that shouldn't run while the game is paused.
+#[derive(SystemSet, Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub struct PausableSystems;
+
+fn spawn_camera(mut commands: Commands) {
+    commands.spawn((
+        Name::new("Camera"),
+        Camera2d,
+        // Positional sound effects are heard relative to the camera.
+        SpatialListener::new(audio::LISTENER_EAR_GAP),
+    ));
+}
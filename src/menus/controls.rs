@@ -0,0 +1,219 @@
+//! The controls menu, reachable from the settings menu.
+//!
+//! Left-click a binding to rebind it, right-click to clear it.
+
+use bevy::{ecs::spawn::SpawnIter, prelude::*};
+
+use crate::{
+    input::{Action, InputMap, action_just_pressed, just_pressed_binding},
+    menus::Menu,
+    theme::prelude::*,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<Rebinding>();
+    app.add_systems(
+        OnEnter(Menu::Controls),
+        (
+            spawn_controls_menu,
+            update_binding_labels,
+            update_status_label,
+        )
+            .chain(),
+    );
+    app.add_systems(OnExit(Menu::Controls), reset_rebinding);
+    app.add_systems(
+        Update,
+        (
+            go_back.run_if(action_just_pressed(Action::Back).and(not_rebinding)),
+            capture_rebinding,
+            (update_binding_labels, update_status_label)
+                .run_if(resource_changed::<InputMap>.or(resource_changed::<Rebinding>)),
+        )
+            .chain()
+            .run_if(in_state(Menu::Controls)),
+    );
+}
+
+/// How many bindings are shown (and can be set) per action.
+const SLOTS: usize = 3;
+
+fn spawn_controls_menu(mut commands: Commands) {
+    commands.spawn((
+        widget::ui_root("Controls Menu"),
+        GlobalZIndex(2),
+        DespawnOnExit(Menu::Controls),
+        children![
+            widget::header("Controls"),
+            controls_grid(),
+            (widget::label(""), ControlsStatusLabel),
+            (
+                Name::new("Controls Buttons"),
+                Node {
+                    column_gap: px(20),
+                    ..default()
+                },
+                children![
+                    widget::button("Reset", reset_bindings),
+                    widget::button("Back", go_back_on_click),
+                ],
+            ),
+        ],
+    ));
+}
+
+fn controls_grid() -> impl Bundle {
+    (
+        Name::new("Controls Grid"),
+        Node {
+            flex_direction: FlexDirection::Column,
+            row_gap: px(6),
+            ..default()
+        },
+        Children::spawn(SpawnIter(Action::ALL.into_iter().map(controls_row))),
+    )
+}
+
+fn controls_row(action: Action) -> impl Bundle {
+    (
+        Name::new("Controls Row"),
+        Node {
+            column_gap: px(10),
+            align_items: AlignItems::Center,
+            ..default()
+        },
+        Children::spawn((
+            Spawn((
+                Name::new("Action"),
+                Node {
+                    width: px(240),
+                    justify_content: JustifyContent::End,
+                    ..default()
+                },
+                children![widget::label(action.name())],
+            )),
+            SpawnIter((0..SLOTS).map(move |slot| binding_button(action, slot))),
+        )),
+    )
+}
+
+/// Marks a button that shows (and rebinds) one binding slot of an action.
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct BindingButton {
+    action: Action,
+    slot: usize,
+}
+
+fn binding_button(action: Action, slot: usize) -> impl Bundle {
+    (
+        widget::button_medium(
+            "",
+            move |click: On<Pointer<Click>>,
+                  mut rebinding: ResMut<Rebinding>,
+                  mut input_map: ResMut<InputMap>| {
+                if click.button == PointerButton::Secondary {
+                    input_map.unbind(action, slot);
+                    rebinding.target = None;
+                } else {
+                    rebinding.target = Some((action, slot));
+                }
+                rebinding.message.clear();
+            },
+        ),
+        BindingButton { action, slot },
+    )
+}
+
+/// The binding slot currently waiting for input, and feedback for the player.
+#[derive(Resource, Default)]
+struct Rebinding {
+    target: Option<(Action, usize)>,
+    message: String,
+}
+
+fn not_rebinding(rebinding: Res<Rebinding>) -> bool {
+    rebinding.target.is_none()
+}
+
+fn reset_rebinding(mut rebinding: ResMut<Rebinding>) {
+    *rebinding = Rebinding::default();
+}
+
+fn capture_rebinding(
+    mut rebinding: ResMut<Rebinding>,
+    mut input_map: ResMut<InputMap>,
+    keys: Res<ButtonInput<KeyCode>>,
+    mouse: Res<ButtonInput<MouseButton>>,
+    gamepads: Query<&Gamepad>,
+) {
+    let Some((action, slot)) = rebinding.target else {
+        return;
+    };
+    if keys.just_pressed(KeyCode::Escape) {
+        rebinding.target = None;
+        return;
+    }
+    let Some(binding) = just_pressed_binding(&keys, &mouse, &gamepads) else {
+        return;
+    };
+
+    rebinding.target = None;
+    if let Err(conflict) = input_map.rebind(action, slot, binding) {
+        rebinding.message = format!("{} is already bound to {}", binding.name(), conflict.name());
+    }
+}
+
+fn update_binding_labels(
+    input_map: Res<InputMap>,
+    rebinding: Res<Rebinding>,
+    button_query: Query<(Entity, &BindingButton)>,
+    children_query: Query<&Children>,
+    mut text_query: Query<&mut Text>,
+) {
+    for (entity, button) in &button_query {
+        let label = if rebinding.target == Some((button.action, button.slot)) {
+            "...".to_string()
+        } else {
+            input_map
+                .bindings(button.action)
+                .get(button.slot)
+                .map_or_else(|| "-".to_string(), |binding| binding.name())
+        };
+        for descendant in children_query.iter_descendants(entity) {
+            if let Ok(mut text) = text_query.get_mut(descendant) {
+                text.0.clone_from(&label);
+            }
+        }
+    }
+}
+
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct ControlsStatusLabel;
+
+fn update_status_label(
+    rebinding: Res<Rebinding>,
+    mut label: Single<&mut Text, With<ControlsStatusLabel>>,
+) {
+    label.0 = match rebinding.target {
+        Some((action, _)) => format!(
+            "Press a key or button for {} (Esc to cancel)",
+            action.name()
+        ),
+        None if !rebinding.message.is_empty() => rebinding.message.clone(),
+        None => "Left-click to rebind, right-click to clear".to_string(),
+    };
+}
+
+fn reset_bindings(_: On<Pointer<Click>>, mut input_map: ResMut<InputMap>) {
+    *input_map = InputMap::default();
+}
+
+fn go_back_on_click(_: On<Pointer<Click>>, mut next_menu: ResMut<NextState<Menu>>) {
+    next_menu.set(Menu::Settings);
+}
+
+fn go_back(mut next_menu: ResMut<NextState<Menu>>) {
+    next_menu.set(Menu::Settings);
+}
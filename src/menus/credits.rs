@@ -1,18 +1,33 @@
 //! The credits menu.
 
-use bevy::{ecs::spawn::SpawnIter, input::common_conditions::input_just_pressed, prelude::*};
+use bevy::{ecs::spawn::SpawnIter, prelude::*};
 
-use crate::{asset_tracking::LoadResource, audio::music, menus::Menu, theme::prelude::*};
+use crate::{
+    asset_tracking::{AssetGroup, LoadAssetGroup, UnloadAssetGroup},
+    input::{Action, action_just_pressed},
+    menus::Menu,
+    theme::prelude::*,
+};
 
 pub(super) fn plugin(app: &mut App) {
-    app.add_systems(OnEnter(Menu::Credits), spawn_credits_menu);
+    app.add_systems(
+        OnEnter(Menu::Credits),
+        (load_credits_assets, spawn_credits_menu),
+    );
+    app.add_systems(OnExit(Menu::Credits), unload_credits_assets);
     app.add_systems(
         Update,
-        go_back.run_if(in_state(Menu::Credits).and(input_just_pressed(KeyCode::Escape))),
+        go_back.run_if(in_state(Menu::Credits).and(action_just_pressed(Action::Back))),
     );
+}
+
+fn load_credits_assets(mut commands: Commands) {
+    commands.queue(LoadAssetGroup(AssetGroup::Credits));
+}
 
-    app.load_resource::<CreditsAssets>();
-    app.add_systems(OnEnter(Menu::Credits), start_credits_music);
+/// The credits are rarely opened twice, so free their assets when leaving.
+fn unload_credits_assets(mut commands: Commands) {
+    commands.queue(UnloadAssetGroup(AssetGroup::Credits));
 }
 
 fn spawn_credits_menu(mut commands: Commands) {
@@ -41,7 +56,12 @@ fn assets() -> impl Bundle {
     grid(vec![
         ["Ducky sprite", "CC0 by Caz Creates Games"],
         ["Button SFX", "CC0 by Jaszunio15"],
+        ["Gameplay SFX", "Synthesized by raw_assets/sound_effects.py"],
         ["Music", "CC BY 3.0 by Kevin MacLeod"],
+        [
+            "Intense music",
+            "Synthesized by raw_assets/intense_music.py",
+        ],
         [
             "Bevy logo",
             "All rights reserved by the Bevy Foundation, permission granted for splash screen use when unmodified",
@@ -84,27 +104,3 @@ fn go_back_on_click(_: On<Pointer<Click>>, mut next_menu: ResMut<NextState<Menu>
 fn go_back(mut next_menu: ResMut<NextState<Menu>>) {
     next_menu.set(Menu::Main);
 }
-
-#[derive(Resource, Asset, Clone, Reflect)]
-#[reflect(Resource)]
-struct CreditsAssets {
-    #[dependency]
-    music: Handle<AudioSource>,
-}
-
-impl FromWorld for CreditsAssets {
-    fn from_world(world: &mut World) -> Self {
-        let assets = world.resource::<AssetServer>();
-        Self {
-            music: assets.load("audio/music/Monkeys Spinning Monkeys.ogg"),
-        }
-    }
-}
-
-fn start_credits_music(mut commands: Commands, credits_music: Res<CreditsAssets>) {
-    commands.spawn((
-        Name::new("Credits Music"),
-        DespawnOnExit(Menu::Credits),
-        music(credits_music.music.clone()),
-    ));
-}
@@ -0,0 +1,140 @@
+//! The custom run menu, for starting a run with a chosen seed or watching the
+//! replay of the last run.
+
+use bevy::prelude::*;
+
+use crate::{
+    asset_tracking::{AssetGroup, ResourceHandles},
+    input::{Action, action_just_pressed},
+    menus::Menu,
+    replay::{ReplayPlayback, load_last_replay},
+    rng::NextSeed,
+    screens::Screen,
+    theme::prelude::*,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<SeedInput>();
+    app.add_systems(OnEnter(Menu::CustomRun), spawn_custom_run_menu);
+    app.add_systems(
+        Update,
+        (
+            go_back.run_if(action_just_pressed(Action::Back)),
+            (type_seed, update_seed_label).chain(),
+        )
+            .run_if(in_state(Menu::CustomRun)),
+    );
+}
+
+fn spawn_custom_run_menu(mut commands: Commands) {
+    commands.spawn((
+        widget::ui_root("Custom Run Menu"),
+        GlobalZIndex(2),
+        DespawnOnExit(Menu::CustomRun),
+        children![
+            widget::header("Custom Run"),
+            (
+                widget::label("Type a seed, or leave it empty for a random one"),
+                CustomRunStatus,
+            ),
+            (
+                Name::new("Seed Field"),
+                Node {
+                    width: px(380),
+                    padding: UiRect::all(px(8)),
+                    justify_content: JustifyContent::Center,
+                    ..default()
+                },
+                BackgroundColor(ui_palette::HUD_BACKGROUND),
+                children![(widget::label(""), SeedLabel)],
+            ),
+            widget::button("Start", start_run),
+            widget::button("Watch Last Replay", watch_last_replay),
+            widget::button("Back", go_back_on_click),
+        ],
+    ));
+}
+
+/// The seed typed into the custom run menu so far.
+#[derive(Resource, Default)]
+struct SeedInput(String);
+
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct SeedLabel;
+
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct CustomRunStatus;
+
+const DIGIT_KEYS: [(KeyCode, KeyCode, char); 10] = [
+    (KeyCode::Digit0, KeyCode::Numpad0, '0'),
+    (KeyCode::Digit1, KeyCode::Numpad1, '1'),
+    (KeyCode::Digit2, KeyCode::Numpad2, '2'),
+    (KeyCode::Digit3, KeyCode::Numpad3, '3'),
+    (KeyCode::Digit4, KeyCode::Numpad4, '4'),
+    (KeyCode::Digit5, KeyCode::Numpad5, '5'),
+    (KeyCode::Digit6, KeyCode::Numpad6, '6'),
+    (KeyCode::Digit7, KeyCode::Numpad7, '7'),
+    (KeyCode::Digit8, KeyCode::Numpad8, '8'),
+    (KeyCode::Digit9, KeyCode::Numpad9, '9'),
+];
+
+/// Read digits straight from the keyboard, since a seed isn't an [`Action`].
+fn type_seed(keys: Res<ButtonInput<KeyCode>>, mut input: ResMut<SeedInput>) {
+    if keys.just_pressed(KeyCode::Backspace) {
+        input.0.pop();
+    }
+    for (key, numpad_key, digit) in DIGIT_KEYS {
+        if !keys.any_just_pressed([key, numpad_key]) {
+            continue;
+        }
+        let typed = format!("{}{digit}", input.0);
+        // Ignore digits that would overflow the seed.
+        if typed.parse::<u64>().is_ok() {
+            input.0 = typed;
+        }
+    }
+}
+
+fn update_seed_label(input: Res<SeedInput>, mut label: Single<&mut Text, With<SeedLabel>>) {
+    label.0 = if input.0.is_empty() {
+        "Random".to_string()
+    } else {
+        input.0.clone()
+    };
+}
+
+fn start_run(
+    _: On<Pointer<Click>>,
+    input: Res<SeedInput>,
+    resource_handles: Res<ResourceHandles>,
+    mut next_seed: ResMut<NextSeed>,
+    mut next_screen: ResMut<NextState<Screen>>,
+) {
+    next_seed.0 = input.0.parse().ok();
+    if resource_handles.is_done(AssetGroup::Gameplay) {
+        next_screen.set(Screen::Gameplay);
+    } else {
+        next_screen.set(Screen::Loading);
+    }
+}
+
+fn watch_last_replay(
+    _: On<Pointer<Click>>,
+    mut commands: Commands,
+    mut status: Single<&mut Text, With<CustomRunStatus>>,
+) {
+    match load_last_replay() {
+        Some(replay) => commands.insert_resource(ReplayPlayback::new(replay)),
+        None => "No replay has been saved yet".clone_into(&mut status.0),
+    }
+}
+
+fn go_back_on_click(_: On<Pointer<Click>>, mut next_menu: ResMut<NextState<Menu>>) {
+    next_menu.set(Menu::Main);
+}
+
+fn go_back(mut next_menu: ResMut<NextState<Menu>>) {
+    next_menu.set(Menu::Main);
+}
@@ -0,0 +1,90 @@
+//! The game over menu, summarizing the run that just ended.
+
+use bevy::{ecs::spawn::SpawnIter, prelude::*};
+
+use crate::{
+    gameplay::stats::RunStats, highscores::Highscores, menus::Menu, rng::GameRng, screens::Screen,
+    theme::prelude::*,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(OnEnter(Menu::GameOver), spawn_game_over_menu);
+}
+
+fn spawn_game_over_menu(
+    mut commands: Commands,
+    stats: Res<RunStats>,
+    rng: Res<GameRng>,
+    highscores: Res<Highscores>,
+) {
+    let rank = match highscores.latest {
+        Some(0) => "New highscore!".to_string(),
+        Some(rank) => format!("#{} on the highscore table", rank + 1),
+        None => String::new(),
+    };
+    let seconds = stats.time.as_secs();
+    let summary = [
+        ("Score", stats.score().to_string()),
+        ("Time", format!("{}:{:02}", seconds / 60, seconds % 60)),
+        ("Kills", stats.total_kills().to_string()),
+        ("Accuracy", format!("{:.0}%", stats.accuracy() * 100.0)),
+        ("Seed", rng.seed().to_string()),
+    ];
+
+    commands.spawn((
+        widget::ui_root("Game Over Menu"),
+        GlobalZIndex(2),
+        DespawnOnExit(Menu::GameOver),
+        children![
+            widget::header("Game Over"),
+            widget::label(rank),
+            summary_grid(summary),
+            widget::button("Play Again", play_again),
+            widget::button("Highscores", open_highscores_menu),
+            widget::button("Quit to title", quit_to_title),
+        ],
+    ));
+}
+
+fn summary_grid(summary: [(&'static str, String); 5]) -> impl Bundle {
+    (
+        Name::new("Run Summary"),
+        Node {
+            display: Display::Grid,
+            row_gap: px(10),
+            column_gap: px(30),
+            grid_template_columns: RepeatedGridTrack::px(2, 200.0),
+            ..default()
+        },
+        Children::spawn(SpawnIter(summary.into_iter().flat_map(|(name, value)| {
+            [
+                (
+                    widget::label(name.to_string()),
+                    Node {
+                        justify_self: JustifySelf::End,
+                        ..default()
+                    },
+                ),
+                (
+                    widget::label(value),
+                    Node {
+                        justify_self: JustifySelf::Start,
+                        ..default()
+                    },
+                ),
+            ]
+        }))),
+    )
+}
+
+fn play_again(_: On<Pointer<Click>>, mut next_screen: ResMut<NextState<Screen>>) {
+    next_screen.set(Screen::Gameplay);
+}
+
+fn open_highscores_menu(_: On<Pointer<Click>>, mut next_menu: ResMut<NextState<Menu>>) {
+    next_menu.set(Menu::Highscores);
+}
+
+fn quit_to_title(_: On<Pointer<Click>>, mut next_screen: ResMut<NextState<Screen>>) {
+    next_screen.set(Screen::Title);
+}
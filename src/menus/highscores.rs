@@ -0,0 +1,101 @@
+//! The highscores menu, listing the best local runs.
+
+use bevy::{ecs::spawn::SpawnIter, prelude::*};
+
+use crate::{
+    highscores::Highscores,
+    input::{Action, action_just_pressed},
+    menus::Menu,
+    screens::Screen,
+    theme::prelude::*,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(OnEnter(Menu::Highscores), spawn_highscores_menu);
+    app.add_systems(
+        Update,
+        go_back.run_if(in_state(Menu::Highscores).and(action_just_pressed(Action::Back))),
+    );
+}
+
+const COLUMNS: [&str; 6] = ["#", "Score", "Time", "Kills", "Date", "Seed"];
+
+fn spawn_highscores_menu(mut commands: Commands, highscores: Res<Highscores>) {
+    let mut cells: Vec<_> = COLUMNS.iter().map(|column| column.to_string()).collect();
+    for (i, entry) in highscores.entries.iter().enumerate() {
+        cells.extend([
+            (i + 1).to_string(),
+            entry.score.to_string(),
+            format!("{}:{:02}", entry.time / 60, entry.time % 60),
+            entry.kills.to_string(),
+            entry.date_label(),
+            entry
+                .seed
+                .map_or_else(|| "-".to_string(), |seed| seed.to_string()),
+        ]);
+    }
+    let latest = highscores.latest;
+
+    commands.spawn((
+        widget::ui_root("Highscores Menu"),
+        GlobalZIndex(2),
+        DespawnOnExit(Menu::Highscores),
+        children![
+            widget::header("Highscores"),
+            widget::label(if highscores.entries.is_empty() {
+                "No runs yet"
+            } else {
+                ""
+            }),
+            (
+                Name::new("Highscore Table"),
+                Node {
+                    display: Display::Grid,
+                    row_gap: px(10),
+                    column_gap: px(30),
+                    grid_template_columns: vec![GridTrack::auto(); COLUMNS.len()],
+                    ..default()
+                },
+                Children::spawn(SpawnIter(cells.into_iter().enumerate().map(
+                    move |(i, text)| {
+                        let row = i / COLUMNS.len();
+                        // Highlight the most recent run; row 0 is the heading.
+                        let color = if row > 0 && latest == Some(row - 1) {
+                            ui_palette::HEADER_TEXT
+                        } else {
+                            ui_palette::LABEL_TEXT
+                        };
+                        (
+                            Name::new("Highscore Cell"),
+                            Text(text),
+                            TextFont::from_font_size(24.0),
+                            TextColor(color),
+                        )
+                    }
+                ))),
+            ),
+            widget::button("Back", go_back_on_click),
+        ],
+    ));
+}
+
+fn go_back_on_click(
+    _: On<Pointer<Click>>,
+    screen: Res<State<Screen>>,
+    mut next_menu: ResMut<NextState<Menu>>,
+) {
+    next_menu.set(previous_menu(screen.get()));
+}
+
+fn go_back(screen: Res<State<Screen>>, mut next_menu: ResMut<NextState<Menu>>) {
+    next_menu.set(previous_menu(screen.get()));
+}
+
+/// The menu the highscores menu was opened from.
+fn previous_menu(screen: &Screen) -> Menu {
+    if screen == &Screen::GameOver {
+        Menu::GameOver
+    } else {
+        Menu::Main
+    }
+}
@@ -1,46 +1,77 @@
 //! The main menu (seen on the title screen).
 
-use bevy::prelude::*;
+use bevy::{ecs::spawn::SpawnWith, prelude::*};
 
-use crate::{asset_tracking::ResourceHandles, menus::Menu, screens::Screen, theme::widget};
+use crate::{
+    asset_tracking::{AssetGroup, ResourceHandles},
+    menus::Menu,
+    save::{has_saved_run, take_saved_run},
+    screens::Screen,
+    theme::widget,
+};
 
 pub(super) fn plugin(app: &mut App) {
     app.add_systems(OnEnter(Menu::Main), spawn_main_menu);
 }
 
 fn spawn_main_menu(mut commands: Commands) {
+    let can_continue = has_saved_run();
     commands.spawn((
         widget::ui_root("Main Menu"),
         GlobalZIndex(2),
         DespawnOnExit(Menu::Main),
-        #[cfg(not(target_family = "wasm"))]
-        children![
-            widget::button("Play", enter_loading_or_gameplay_screen),
-            widget::button("Settings", open_settings_menu),
-            widget::button("Credits", open_credits_menu),
-            widget::button("Exit", exit_app),
-        ],
-        #[cfg(target_family = "wasm")]
-        children![
-            widget::button("Play", enter_loading_or_gameplay_screen),
-            widget::button("Settings", open_settings_menu),
-            widget::button("Credits", open_credits_menu),
-        ],
+        Children::spawn(SpawnWith(move |parent: &mut ChildSpawner| {
+            if can_continue {
+                parent.spawn(widget::button("Continue", continue_run));
+            }
+            parent.spawn(widget::button("Play", enter_intro_screen));
+            parent.spawn(widget::button("Custom Run", open_custom_run_menu));
+            parent.spawn(widget::button("Highscores", open_highscores_menu));
+            parent.spawn(widget::button("Settings", open_settings_menu));
+            parent.spawn(widget::button("Credits", open_credits_menu));
+            #[cfg(not(target_family = "wasm"))]
+            parent.spawn(widget::button("Exit", exit_app));
+        })),
     ));
 }
 
-fn enter_loading_or_gameplay_screen(
+fn continue_run(
     _: On<Pointer<Click>>,
+    mut commands: Commands,
+    registry: Res<AppTypeRegistry>,
     resource_handles: Res<ResourceHandles>,
     mut next_screen: ResMut<NextState<Screen>>,
+    mut next_menu: ResMut<NextState<Menu>>,
 ) {
-    if resource_handles.is_all_done() {
+    match take_saved_run(&registry.read()) {
+        Ok(saved_run) => commands.insert_resource(saved_run),
+        Err(error) => {
+            warn!("Failed to continue saved run: {error}");
+            // An unreadable save is moved aside, so rebuild the menu without the
+            // "Continue" button.
+            next_menu.set(Menu::Main);
+            return;
+        }
+    }
+    if resource_handles.is_done(AssetGroup::Gameplay) {
         next_screen.set(Screen::Gameplay);
     } else {
         next_screen.set(Screen::Loading);
     }
 }
 
+fn enter_intro_screen(_: On<Pointer<Click>>, mut next_screen: ResMut<NextState<Screen>>) {
+    next_screen.set(Screen::Intro);
+}
+
+fn open_custom_run_menu(_: On<Pointer<Click>>, mut next_menu: ResMut<NextState<Menu>>) {
+    next_menu.set(Menu::CustomRun);
+}
+
+fn open_highscores_menu(_: On<Pointer<Click>>, mut next_menu: ResMut<NextState<Menu>>) {
+    next_menu.set(Menu::Highscores);
+}
+
 fn open_settings_menu(_: On<Pointer<Click>>, mut next_menu: ResMut<NextState<Menu>>) {
     next_menu.set(Menu::Settings);
 }
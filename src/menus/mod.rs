@@ -1,6 +1,10 @@
 //! The game's menus and transitions between them.
 
+mod controls;
 mod credits;
+mod custom_run;
+mod game_over;
+mod highscores;
 mod main;
 mod pause;
 mod settings;
@@ -11,7 +15,11 @@ pub(super) fn plugin(app: &mut App) {
     app.init_state::<Menu>();
 
     app.add_plugins((
+        controls::plugin,
         credits::plugin,
+        custom_run::plugin,
+        game_over::plugin,
+        highscores::plugin,
         main::plugin,
         settings::plugin,
         pause::plugin,
@@ -24,6 +32,10 @@ pub enum Menu {
     None,
     Main,
     Credits,
+    CustomRun,
+    Highscores,
     Settings,
+    Controls,
     Pause,
+    GameOver,
 }
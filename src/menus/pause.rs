@@ -1,28 +1,40 @@
 //! The pause menu.
 
-use bevy::{input::common_conditions::input_just_pressed, prelude::*};
+use bevy::{ecs::spawn::SpawnWith, prelude::*};
 
-use crate::{menus::Menu, screens::Screen, theme::widget};
+use crate::{
+    input::{Action, action_just_pressed},
+    menus::Menu,
+    replay::ReplayPlayback,
+    save::save_run,
+    screens::Screen,
+    theme::widget,
+};
 
 pub(super) fn plugin(app: &mut App) {
     app.add_systems(OnEnter(Menu::Pause), spawn_pause_menu);
     app.add_systems(
         Update,
-        go_back.run_if(in_state(Menu::Pause).and(input_just_pressed(KeyCode::Escape))),
+        go_back.run_if(in_state(Menu::Pause).and(action_just_pressed(Action::Back))),
     );
 }
 
-fn spawn_pause_menu(mut commands: Commands) {
+fn spawn_pause_menu(mut commands: Commands, playback: Option<Res<ReplayPlayback>>) {
+    // There's nothing to save while watching a replay.
+    let can_save = playback.is_none();
     commands.spawn((
         widget::ui_root("Pause Menu"),
         GlobalZIndex(2),
         DespawnOnExit(Menu::Pause),
-        children![
-            widget::header("Game paused"),
-            widget::button("Continue", close_menu),
-            widget::button("Settings", open_settings_menu),
-            widget::button("Quit to title", quit_to_title),
-        ],
+        Children::spawn(SpawnWith(move |parent: &mut ChildSpawner| {
+            parent.spawn(widget::header("Game paused"));
+            parent.spawn(widget::button("Continue", close_menu));
+            parent.spawn(widget::button("Settings", open_settings_menu));
+            if can_save {
+                parent.spawn(widget::button("Save & Quit", save_and_quit));
+            }
+            parent.spawn(widget::button("Quit to title", quit_to_title));
+        })),
     ));
 }
 
@@ -34,6 +46,15 @@ fn close_menu(_: On<Pointer<Click>>, mut next_menu: ResMut<NextState<Menu>>) {
     next_menu.set(Menu::None);
 }
 
+fn save_and_quit(
+    _: On<Pointer<Click>>,
+    mut commands: Commands,
+    mut next_screen: ResMut<NextState<Screen>>,
+) {
+    commands.run_system_cached(save_run);
+    next_screen.set(Screen::Title);
+}
+
 fn quit_to_title(_: On<Pointer<Click>>, mut next_screen: ResMut<NextState<Screen>>) {
     next_screen.set(Screen::Title);
 }
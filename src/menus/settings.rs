@@ -2,20 +2,32 @@
 //!
 //! Additional settings and accessibility options should go here.
 
-use bevy::{audio::Volume, input::common_conditions::input_just_pressed, prelude::*};
+use bevy::{ecs::spawn::SpawnWith, prelude::*};
 
-use crate::{menus::Menu, screens::Screen, theme::prelude::*};
+use crate::{
+    audio::{AudioChannel, AudioVolumes},
+    input::{Action, action_just_pressed},
+    menus::Menu,
+    screens::Screen,
+    settings::{DisplayMode, Settings},
+    theme::prelude::*,
+};
 
 pub(super) fn plugin(app: &mut App) {
     app.add_systems(OnEnter(Menu::Settings), spawn_settings_menu);
     app.add_systems(
         Update,
-        go_back.run_if(in_state(Menu::Settings).and(input_just_pressed(KeyCode::Escape))),
+        go_back.run_if(in_state(Menu::Settings).and(action_just_pressed(Action::Back))),
     );
 
     app.add_systems(
         Update,
-        update_global_volume_label.run_if(in_state(Menu::Settings)),
+        (
+            update_volume_labels,
+            update_mute_labels,
+            update_toggle_labels,
+        )
+            .run_if(in_state(Menu::Settings)),
     );
 }
 
@@ -27,6 +39,7 @@ fn spawn_settings_menu(mut commands: Commands) {
         children![
             widget::header("Settings"),
             settings_grid(),
+            widget::button("Controls", open_controls_menu),
             widget::button("Back", go_back_on_click),
         ],
     ));
@@ -42,38 +55,72 @@ fn settings_grid() -> impl Bundle {
             grid_template_columns: RepeatedGridTrack::px(2, 400.0),
             ..default()
         },
-        children![
-            (
-                widget::label("Master Volume"),
-                Node {
-                    justify_self: JustifySelf::End,
-                    ..default()
-                }
-            ),
-            global_volume_widget(),
-        ],
+        Children::spawn(SpawnWith(|parent: &mut ChildSpawner| {
+            for channel in AudioChannel::ALL {
+                parent.spawn(settings_label(channel.name()));
+                parent.spawn(volume_widget(channel));
+            }
+            for toggle in SettingsToggle::ALL {
+                parent.spawn(settings_label(toggle.name()));
+                parent.spawn(toggle_widget(toggle));
+            }
+        })),
+    )
+}
+
+fn settings_label(text: &'static str) -> impl Bundle {
+    (
+        widget::label(text),
+        Node {
+            justify_self: JustifySelf::End,
+            ..default()
+        },
     )
 }
 
-fn global_volume_widget() -> impl Bundle {
+fn volume_widget(channel: AudioChannel) -> impl Bundle {
     (
-        Name::new("Global Volume Widget"),
+        Name::new("Volume Widget"),
         Node {
             justify_self: JustifySelf::Start,
+            align_items: AlignItems::Center,
+            column_gap: px(10),
             ..default()
         },
         children![
-            widget::button_small("-", lower_global_volume),
+            widget::button_small(
+                "-",
+                move |_: On<Pointer<Click>>, mut volumes: ResMut<AudioVolumes>| {
+                    let channel = volumes.channel_mut(channel);
+                    channel.volume = (channel.volume - 0.1).max(MIN_VOLUME);
+                }
+            ),
             (
                 Name::new("Current Volume"),
                 Node {
-                    padding: UiRect::horizontal(px(10)),
+                    width: px(80),
                     justify_content: JustifyContent::Center,
                     ..default()
                 },
-                children![(widget::label(""), GlobalVolumeLabel)],
+                children![(widget::label(""), VolumeLabel(channel))],
+            ),
+            widget::button_small(
+                "+",
+                move |_: On<Pointer<Click>>, mut volumes: ResMut<AudioVolumes>| {
+                    let channel = volumes.channel_mut(channel);
+                    channel.volume = (channel.volume + 0.1).min(MAX_VOLUME);
+                }
+            ),
+            (
+                widget::button_medium(
+                    "",
+                    move |_: On<Pointer<Click>>, mut volumes: ResMut<AudioVolumes>| {
+                        let channel = volumes.channel_mut(channel);
+                        channel.muted = !channel.muted;
+                    }
+                ),
+                MuteButton(channel),
             ),
-            widget::button_small("+", raise_global_volume),
         ],
     )
 }
@@ -81,26 +128,136 @@ fn global_volume_widget() -> impl Bundle {
 const MIN_VOLUME: f32 = 0.0;
 const MAX_VOLUME: f32 = 3.0;
 
-fn lower_global_volume(_: On<Pointer<Click>>, mut global_volume: ResMut<GlobalVolume>) {
-    let linear = (global_volume.volume.to_linear() - 0.1).max(MIN_VOLUME);
-    global_volume.volume = Volume::Linear(linear);
-}
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct VolumeLabel(AudioChannel);
 
-fn raise_global_volume(_: On<Pointer<Click>>, mut global_volume: ResMut<GlobalVolume>) {
-    let linear = (global_volume.volume.to_linear() + 0.1).min(MAX_VOLUME);
-    global_volume.volume = Volume::Linear(linear);
+fn update_volume_labels(
+    volumes: Res<AudioVolumes>,
+    mut label_query: Query<(&VolumeLabel, &mut Text)>,
+) {
+    for (label, mut text) in &mut label_query {
+        let percent = 100.0 * volumes.channel(label.0).volume;
+        text.0 = format!("{percent:3.0}%");
+    }
 }
 
 #[derive(Component, Reflect)]
 #[reflect(Component)]
-struct GlobalVolumeLabel;
+struct MuteButton(AudioChannel);
 
-fn update_global_volume_label(
-    global_volume: Res<GlobalVolume>,
-    mut label: Single<&mut Text, With<GlobalVolumeLabel>>,
+fn update_mute_labels(
+    volumes: Res<AudioVolumes>,
+    button_query: Query<(Entity, &MuteButton)>,
+    children_query: Query<&Children>,
+    mut text_query: Query<&mut Text>,
 ) {
-    let percent = 100.0 * global_volume.volume.to_linear();
-    label.0 = format!("{percent:3.0}%");
+    for (entity, button) in &button_query {
+        let label = if volumes.channel(button.0).muted {
+            "Unmute"
+        } else {
+            "Mute"
+        };
+        for descendant in children_query.iter_descendants(entity) {
+            if let Ok(mut text) = text_query.get_mut(descendant) {
+                label.clone_into(&mut text.0);
+            }
+        }
+    }
+}
+
+/// A boolean setting that can be flipped with a button in the settings grid.
+#[derive(Component, Reflect, Clone, Copy)]
+#[reflect(Component)]
+enum SettingsToggle {
+    Fullscreen,
+    ReduceFlashing,
+    DamageNumbers,
+    HitFlash,
+}
+
+impl SettingsToggle {
+    const ALL: [Self; 4] = [
+        Self::Fullscreen,
+        Self::ReduceFlashing,
+        Self::DamageNumbers,
+        Self::HitFlash,
+    ];
+
+    fn name(self) -> &'static str {
+        match self {
+            Self::Fullscreen => "Fullscreen",
+            Self::ReduceFlashing => "Reduce Flashing",
+            Self::DamageNumbers => "Damage Numbers",
+            Self::HitFlash => "Hit Flash",
+        }
+    }
+
+    fn get(self, settings: &Settings) -> bool {
+        match self {
+            Self::Fullscreen => settings.display_mode == DisplayMode::Fullscreen,
+            Self::ReduceFlashing => settings.accessibility.reduce_flashing,
+            Self::DamageNumbers => settings.effects.damage_numbers,
+            Self::HitFlash => settings.effects.hit_flash,
+        }
+    }
+
+    fn toggle(self, settings: &mut Settings) {
+        match self {
+            Self::Fullscreen => {
+                settings.display_mode = match settings.display_mode {
+                    DisplayMode::Windowed => DisplayMode::Fullscreen,
+                    DisplayMode::Fullscreen => DisplayMode::Windowed,
+                }
+            }
+            Self::ReduceFlashing => {
+                settings.accessibility.reduce_flashing = !settings.accessibility.reduce_flashing
+            }
+            Self::DamageNumbers => {
+                settings.effects.damage_numbers = !settings.effects.damage_numbers
+            }
+            Self::HitFlash => settings.effects.hit_flash = !settings.effects.hit_flash,
+        }
+    }
+}
+
+fn toggle_widget(toggle: SettingsToggle) -> impl Bundle {
+    (
+        Name::new("Toggle Widget"),
+        Node {
+            justify_self: JustifySelf::Start,
+            ..default()
+        },
+        children![(
+            widget::button_medium(
+                "",
+                move |_: On<Pointer<Click>>, mut settings: ResMut<Settings>| {
+                    toggle.toggle(&mut settings);
+                }
+            ),
+            toggle,
+        )],
+    )
+}
+
+fn update_toggle_labels(
+    settings: Res<Settings>,
+    toggle_query: Query<(Entity, &SettingsToggle)>,
+    children_query: Query<&Children>,
+    mut text_query: Query<&mut Text>,
+) {
+    for (entity, toggle) in &toggle_query {
+        let label = if toggle.get(&settings) { "On" } else { "Off" };
+        for descendant in children_query.iter_descendants(entity) {
+            if let Ok(mut text) = text_query.get_mut(descendant) {
+                label.clone_into(&mut text.0);
+            }
+        }
+    }
+}
+
+fn open_controls_menu(_: On<Pointer<Click>>, mut next_menu: ResMut<NextState<Menu>>) {
+    next_menu.set(Menu::Controls);
 }
 
 fn go_back_on_click(
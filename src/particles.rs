@@ -0,0 +1,199 @@
+//! A lightweight CPU particle system.
+//!
+//! Spawn a [`ParticleEmitter`] to emit particles. Each particle is a plain
+//! [`Sprite`] that flies in a straight line and changes colour and scale over its
+//! lifetime, as configured by the emitter that spawned it.
+
+use std::{f32::consts::PI, ops::Range};
+
+use bevy::prelude::*;
+use rand::Rng;
+
+use crate::{AppSystems, PausableSystems, rng::GameRng, screens::Screen};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(
+        Update,
+        (
+            tick_particles.in_set(AppSystems::TickTimers),
+            (emit_particles, update_particles)
+                .chain()
+                .in_set(AppSystems::Update),
+        )
+            .in_set(PausableSystems),
+    );
+    app.add_systems(OnExit(Screen::Gameplay), despawn_particles);
+}
+
+/// Emits particles from its [`GlobalTransform`].
+#[derive(Component, Clone, Reflect)]
+#[reflect(Component)]
+#[require(Transform, EmissionProgress)]
+pub struct ParticleEmitter {
+    pub mode: EmissionMode,
+    /// The sprite each particle starts out as. If it has a [`TextureAtlas`], each
+    /// particle picks a random index in `atlas_frames`, unless that is empty.
+    pub sprite: Sprite,
+    pub atlas_frames: Range<usize>,
+    /// How long each particle lives, in seconds.
+    pub lifetime: Range<f32>,
+    /// How fast particles start out, in pixels per second.
+    pub speed: Range<f32>,
+    /// The angle particles are emitted at, in radians.
+    pub direction: f32,
+    /// How far particles may stray from `direction`, in radians to either side.
+    pub spread: f32,
+    /// The fraction of their velocity particles lose per second.
+    pub drag: f32,
+    pub start_color: Color,
+    pub end_color: Color,
+    pub start_scale: f32,
+    pub end_scale: f32,
+}
+
+impl Default for ParticleEmitter {
+    fn default() -> Self {
+        Self {
+            mode: EmissionMode::Burst(8),
+            sprite: Sprite::from_color(Color::WHITE, Vec2::splat(4.0)),
+            atlas_frames: 0..0,
+            lifetime: 0.5..1.0,
+            speed: 50.0..100.0,
+            direction: 0.0,
+            spread: PI,
+            drag: 0.0,
+            start_color: Color::WHITE,
+            end_color: Color::WHITE.with_alpha(0.0),
+            start_scale: 1.0,
+            end_scale: 1.0,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Reflect)]
+pub enum EmissionMode {
+    /// Emit this many particles at once, then despawn the emitter.
+    Burst(u32),
+    /// Keep emitting this many particles per second.
+    Continuous(f32),
+}
+
+/// Particles owed by a continuous emitter that haven't been spawned yet.
+#[derive(Component, Default)]
+struct EmissionProgress(f32);
+
+#[derive(Component)]
+struct Particle {
+    timer: Timer,
+    velocity: Vec2,
+    drag: f32,
+    start_color: Color,
+    end_color: Color,
+    start_scale: f32,
+    end_scale: f32,
+}
+
+impl ParticleEmitter {
+    fn particle(&self, position: Vec3, rng: &mut impl Rng) -> impl Bundle {
+        let angle = self.direction + self.spread * rng.random_range(-1.0..=1.0);
+        let speed = sample(&self.speed, rng);
+        let mut sprite = self.sprite.clone();
+        sprite.color = self.start_color;
+        if let Some(atlas) = sprite.texture_atlas.as_mut()
+            && !self.atlas_frames.is_empty()
+        {
+            atlas.index = rng.random_range(self.atlas_frames.clone());
+        }
+        (
+            Name::new("Particle"),
+            Particle {
+                timer: Timer::from_seconds(sample(&self.lifetime, rng), TimerMode::Once),
+                velocity: Vec2::from_angle(angle) * speed,
+                drag: self.drag,
+                start_color: self.start_color,
+                end_color: self.end_color,
+                start_scale: self.start_scale,
+                end_scale: self.end_scale,
+            },
+            sprite,
+            Transform::from_translation(position).with_scale(Vec3::splat(self.start_scale)),
+        )
+    }
+}
+
+/// Pick a value in `range`, allowing it to be empty.
+fn sample(range: &Range<f32>, rng: &mut impl Rng) -> f32 {
+    range.start + (range.end - range.start) * rng.random::<f32>()
+}
+
+fn emit_particles(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut rng: ResMut<GameRng>,
+    mut emitter_query: Query<(
+        Entity,
+        &ParticleEmitter,
+        &mut EmissionProgress,
+        Ref<GlobalTransform>,
+    )>,
+) {
+    let rng = rng.stream("particles");
+    for (entity, emitter, mut progress, transform) in &mut emitter_query {
+        // Wait for the emitter's position to be propagated after it is spawned.
+        if transform.is_added() {
+            continue;
+        }
+        let count = match emitter.mode {
+            EmissionMode::Burst(count) => {
+                commands.entity(entity).despawn();
+                count
+            }
+            EmissionMode::Continuous(rate) => {
+                progress.0 += rate * time.delta_secs();
+                let count = progress.0.floor();
+                progress.0 -= count;
+                count as u32
+            }
+        };
+        let position = transform.translation();
+        for _ in 0..count {
+            commands.spawn(emitter.particle(position, rng));
+        }
+    }
+}
+
+fn tick_particles(time: Res<Time>, mut particle_query: Query<&mut Particle>) {
+    for mut particle in &mut particle_query {
+        particle.timer.tick(time.delta());
+    }
+}
+
+fn update_particles(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut particle_query: Query<(Entity, &mut Particle, &mut Transform, &mut Sprite)>,
+) {
+    let dt = time.delta_secs();
+    for (entity, mut particle, mut transform, mut sprite) in &mut particle_query {
+        if particle.timer.is_finished() {
+            commands.entity(entity).despawn();
+            continue;
+        }
+        transform.translation += (particle.velocity * dt).extend(0.0);
+        let drag = (1.0 - particle.drag * dt).max(0.0);
+        particle.velocity *= drag;
+
+        let t = particle.timer.fraction();
+        sprite.color = particle.start_color.mix(&particle.end_color, t);
+        transform.scale = Vec3::splat(particle.start_scale.lerp(particle.end_scale, t));
+    }
+}
+
+fn despawn_particles(
+    mut commands: Commands,
+    query: Query<Entity, Or<(With<Particle>, With<ParticleEmitter>)>>,
+) {
+    for entity in &query {
+        commands.entity(entity).despawn();
+    }
+}
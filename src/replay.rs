@@ -0,0 +1,501 @@
+//! Recording and deterministic playback of runs.
+//!
+//! Every run is recorded tick by tick: the [`FixedActionState`] each tick of
+//! [`FixedUpdate`] sees. Ticks while the game is paused don't simulate anything,
+//! so they are left out. When the run ends, the recording is saved along with
+//! the run's seed. Playing it back reseeds [`GameRng`] and feeds the recorded
+//! input into [`FixedActionState`] one tick at a time, so the regular gameplay
+//! systems reproduce the run however many ticks each frame happens to run. A
+//! checksum of the world is stored every [`CHECKSUM_INTERVAL`] ticks to detect
+//! when playback desyncs.
+
+use std::io;
+
+use bevy::{ecs::system::SystemParam, prelude::*};
+
+use crate::{
+    Pause,
+    asset_tracking::{AssetGroup, ResourceHandles},
+    gameplay::{
+        enemy::Enemy,
+        gun::Gun,
+        player::{Health, Player},
+        stats::RunStats,
+    },
+    input::{
+        ActionState, FixedActionState, latch_fixed_actions, tick_fixed_actions, update_action_state,
+    },
+    rng::{GameRng, NextSeed},
+    screens::Screen,
+    storage,
+    theme::prelude::*,
+    time_scale::TimeScale,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<ReplayRecorder>();
+
+    app.add_systems(
+        OnEnter(Screen::Gameplay),
+        (reset_fixed_time, start_recording, spawn_replay_panel),
+    );
+    app.add_systems(OnExit(Screen::Gameplay), (save_recording, finish_playback));
+    app.add_systems(OnEnter(Screen::Title), stop_playback);
+    app.add_systems(OnExit(Screen::GameOver), stop_playback);
+
+    app.add_systems(
+        PreUpdate,
+        ignore_viewer_input
+            .after(update_action_state)
+            .before(latch_fixed_actions)
+            .run_if(playback_running),
+    );
+    // Paused ticks don't simulate anything, so they are neither recorded nor
+    // played back.
+    let simulating = in_state(Screen::Gameplay).and(in_state(Pause(false)));
+    app.add_systems(
+        FixedPreUpdate,
+        (
+            record_tick.run_if(simulating.clone().and(not(playback_running))),
+            apply_replay_tick.run_if(simulating.clone().and(playback_running)),
+        )
+            .after(tick_fixed_actions),
+    );
+    app.add_systems(
+        FixedLast,
+        (
+            record_checksum.run_if(simulating.clone().and(not(playback_running))),
+            check_replay_tick.run_if(simulating.and(playback_running)),
+        ),
+    );
+    app.add_systems(
+        Update,
+        (
+            start_playback.run_if(resource_exists::<ReplayPlayback>),
+            end_playback.run_if(in_state(Screen::Gameplay).and(playback_running)),
+            update_replay_label.run_if(resource_exists::<ReplayPlayback>),
+        ),
+    );
+}
+
+/// The storage key the last run's replay is saved under.
+const STORAGE_KEY: &str = "replay";
+
+/// How many ticks apart world checksums are stored.
+const CHECKSUM_INTERVAL: u32 = 30;
+
+/// The playback speeds offered while watching a replay.
+const PLAYBACK_SPEEDS: [f32; 6] = [0.0, 0.25, 0.5, 1.0, 2.0, 4.0];
+
+/// The recorded input of a single tick.
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct ReplayTick {
+    /// The actions held this tick and the last, as in [`FixedActionState::bits`].
+    pressed: u16,
+    previous: u16,
+    aim: Option<Vec2>,
+}
+
+/// A recorded run.
+#[derive(Clone, Debug, Default)]
+pub struct Replay {
+    seed: u64,
+    ticks: Vec<ReplayTick>,
+    /// World checksums, stored every [`CHECKSUM_INTERVAL`] ticks.
+    checksums: Vec<u64>,
+}
+
+/// Identifies replay files, followed by the format version.
+const MAGIC: &[u8; 4] = b"BJ7R";
+/// Version 1 recorded input per frame, with the frame's time delta.
+const VERSION: u8 = 2;
+
+const AIM_FLAG: u8 = 1 << 0;
+
+impl Replay {
+    /// Encode the replay in a compact little-endian binary format.
+    fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(32 + self.ticks.len() * 13 + self.checksums.len() * 8);
+        bytes.extend_from_slice(MAGIC);
+        bytes.push(VERSION);
+        bytes.extend_from_slice(&self.seed.to_le_bytes());
+        bytes.extend_from_slice(&(self.ticks.len() as u32).to_le_bytes());
+        for tick in &self.ticks {
+            bytes.extend_from_slice(&tick.pressed.to_le_bytes());
+            bytes.extend_from_slice(&tick.previous.to_le_bytes());
+            bytes.push(if tick.aim.is_some() { AIM_FLAG } else { 0 });
+            if let Some(aim) = tick.aim {
+                bytes.extend_from_slice(&aim.x.to_le_bytes());
+                bytes.extend_from_slice(&aim.y.to_le_bytes());
+            }
+        }
+        bytes.extend_from_slice(&(self.checksums.len() as u32).to_le_bytes());
+        for checksum in &self.checksums {
+            bytes.extend_from_slice(&checksum.to_le_bytes());
+        }
+        bytes
+    }
+
+    fn from_bytes(bytes: &[u8]) -> io::Result<Self> {
+        let mut reader = ByteReader(bytes);
+        if reader.take::<4>()? != *MAGIC {
+            return Err(invalid_data("not a replay file"));
+        }
+        let [version] = reader.take()?;
+        if version != VERSION {
+            return Err(invalid_data(format!(
+                "unsupported replay version {version}"
+            )));
+        }
+        let seed = u64::from_le_bytes(reader.take()?);
+        let tick_count = u32::from_le_bytes(reader.take()?);
+        let mut ticks = Vec::with_capacity(tick_count.min(1 << 20) as usize);
+        for _ in 0..tick_count {
+            let pressed = u16::from_le_bytes(reader.take()?);
+            let previous = u16::from_le_bytes(reader.take()?);
+            let [flags] = reader.take()?;
+            let aim = if flags & AIM_FLAG != 0 {
+                let x = f32::from_le_bytes(reader.take()?);
+                let y = f32::from_le_bytes(reader.take()?);
+                Some(Vec2::new(x, y))
+            } else {
+                None
+            };
+            ticks.push(ReplayTick {
+                pressed,
+                previous,
+                aim,
+            });
+        }
+        let checksum_count = u32::from_le_bytes(reader.take()?);
+        let checksums = (0..checksum_count)
+            .map(|_| reader.take().map(u64::from_le_bytes))
+            .collect::<io::Result<_>>()?;
+        Ok(Self {
+            seed,
+            ticks,
+            checksums,
+        })
+    }
+}
+
+fn invalid_data(message: impl Into<String>) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.into())
+}
+
+/// Reads fixed-size chunks off the front of a byte slice.
+struct ByteReader<'a>(&'a [u8]);
+
+impl ByteReader<'_> {
+    fn take<const N: usize>(&mut self) -> io::Result<[u8; N]> {
+        let (chunk, rest) = self
+            .0
+            .split_first_chunk()
+            .ok_or_else(|| invalid_data("replay file is truncated"))?;
+        self.0 = rest;
+        Ok(*chunk)
+    }
+}
+
+/// Load the replay of the last run, if there is one.
+pub fn load_last_replay() -> Option<Replay> {
+    match storage::read_bytes(STORAGE_KEY) {
+        Ok(Some(bytes)) => match Replay::from_bytes(&bytes) {
+            Ok(replay) => Some(replay),
+            Err(error) => {
+                warn!("Failed to load replay: {error}");
+                None
+            }
+        },
+        Ok(None) => None,
+        Err(error) => {
+            warn!("Failed to read replay: {error}");
+            None
+        }
+    }
+}
+
+/// The state of the world that gameplay input affects, hashed to detect desyncs.
+#[derive(SystemParam)]
+struct WorldState<'w, 's> {
+    stats: Res<'w, RunStats>,
+    player_query: Query<'w, 's, (&'static Transform, &'static Health), With<Player>>,
+    gun_query: Query<'w, 's, &'static Gun>,
+    enemy_query: Query<'w, 's, (&'static Transform, &'static Enemy)>,
+}
+
+impl WorldState<'_, '_> {
+    fn checksum(&self) -> u64 {
+        let mut checksum = Checksum::default();
+        checksum.write(self.stats.total_kills().into());
+        checksum.write(self.stats.shots_fired.into());
+        for (transform, health) in &self.player_query {
+            checksum.write_vec(transform.translation.xy());
+            checksum.write(health.current.to_bits().into());
+        }
+        for gun in &self.gun_query {
+            checksum.write(gun.ammo().into());
+        }
+        // Enemies are visited in no particular order, so combine them with a sum.
+        let enemies = self
+            .enemy_query
+            .iter()
+            .map(|(transform, enemy)| {
+                let mut checksum = Checksum::default();
+                checksum.write_vec(transform.translation.xy());
+                checksum.write(enemy.health().to_bits().into());
+                checksum.0
+            })
+            .fold(0u64, u64::wrapping_add);
+        checksum.write(enemies);
+        checksum.0
+    }
+}
+
+/// An FNV-1a hash over 64-bit words.
+struct Checksum(u64);
+
+impl Default for Checksum {
+    fn default() -> Self {
+        Self(0xcbf2_9ce4_8422_2325)
+    }
+}
+
+impl Checksum {
+    fn write(&mut self, value: u64) {
+        for byte in value.to_le_bytes() {
+            self.0 = (self.0 ^ u64::from(byte)).wrapping_mul(0x0000_0100_0000_01b3);
+        }
+    }
+
+    fn write_vec(&mut self, value: Vec2) {
+        self.write(value.x.to_bits().into());
+        self.write(value.y.to_bits().into());
+    }
+}
+
+/// The recording of the current run.
+#[derive(Resource, Default)]
+struct ReplayRecorder(Replay);
+
+/// Discard leftover fixed timestep time and input from before the run, so every
+/// run starts from the same tick input.
+fn reset_fixed_time(
+    mut fixed_time: ResMut<Time<Fixed>>,
+    mut fixed_actions: ResMut<FixedActionState>,
+) {
+    let overstep = fixed_time.overstep();
+    fixed_time.discard_overstep(overstep);
+    *fixed_actions = FixedActionState::default();
+}
+
+fn start_recording(mut recorder: ResMut<ReplayRecorder>) {
+    recorder.0 = Replay::default();
+}
+
+fn record_tick(actions: Res<FixedActionState>, mut recorder: ResMut<ReplayRecorder>) {
+    let (pressed, previous) = actions.bits();
+    recorder.0.ticks.push(ReplayTick {
+        pressed,
+        previous,
+        aim: actions.aim(),
+    });
+}
+
+/// Store a checksum once the tick has been simulated.
+fn record_checksum(world: WorldState, mut recorder: ResMut<ReplayRecorder>) {
+    let replay = &mut recorder.0;
+    if (replay.ticks.len() as u32).is_multiple_of(CHECKSUM_INTERVAL) {
+        replay.checksums.push(world.checksum());
+    }
+}
+
+fn save_recording(
+    rng: Res<GameRng>,
+    stats: Res<RunStats>,
+    playback: Option<Res<ReplayPlayback>>,
+    mut recorder: ResMut<ReplayRecorder>,
+) {
+    // A continued run didn't start from its seed, and cheats aren't recorded, so
+    // the recording of either can't be played back.
+    if playback.is_some() || stats.resumed || stats.cheated {
+        return;
+    }
+    let mut replay = std::mem::take(&mut recorder.0);
+    replay.seed = rng.seed();
+    if let Err(error) = storage::write_bytes(STORAGE_KEY, &replay.to_bytes()) {
+        warn!("Failed to save replay: {error}");
+    }
+}
+
+/// A replay being watched.
+#[derive(Resource)]
+pub struct ReplayPlayback {
+    replay: Replay,
+    /// The index of the tick being played.
+    tick: usize,
+    started: bool,
+    finished: bool,
+    speed: f32,
+    /// The first tick whose checksum didn't match the recording.
+    desync: Option<usize>,
+}
+
+impl ReplayPlayback {
+    /// Watch `replay`, starting a new run as soon as assets are loaded.
+    pub fn new(replay: Replay) -> Self {
+        Self {
+            replay,
+            tick: 0,
+            started: false,
+            finished: false,
+            speed: 1.0,
+            desync: None,
+        }
+    }
+
+    /// The first tick whose checksum didn't match the recording, if any.
+    pub fn desync(&self) -> Option<usize> {
+        self.desync
+    }
+}
+
+fn playback_running(playback: Option<Res<ReplayPlayback>>) -> bool {
+    playback.is_some_and(|playback| playback.started && !playback.finished)
+}
+
+/// Start the run being replayed.
+fn start_playback(
+    resource_handles: Res<ResourceHandles>,
+    mut playback: ResMut<ReplayPlayback>,
+    mut next_seed: ResMut<NextSeed>,
+    mut next_screen: ResMut<NextState<Screen>>,
+) {
+    if playback.started || !resource_handles.is_done(AssetGroup::Gameplay) {
+        return;
+    }
+    if playback.replay.ticks.is_empty() {
+        warn!("Replay is empty");
+        playback.finished = true;
+        return;
+    }
+    next_seed.0 = Some(playback.replay.seed);
+    next_screen.set(Screen::Gameplay);
+    playback.started = true;
+}
+
+/// Keep whoever is watching from playing along, or from opening the pause menu.
+fn ignore_viewer_input(mut actions: ResMut<ActionState>) {
+    actions.set(0, 0, None);
+}
+
+/// Replace this tick's input with the recorded input. Past the end of the
+/// recording, nothing is held.
+fn apply_replay_tick(playback: Res<ReplayPlayback>, mut actions: ResMut<FixedActionState>) {
+    match playback.replay.ticks.get(playback.tick) {
+        Some(tick) => actions.set(tick.pressed, tick.previous, tick.aim),
+        None => actions.set(0, 0, None),
+    }
+}
+
+/// Check the world against the recording once the tick has been simulated.
+fn check_replay_tick(world: WorldState, mut playback: ResMut<ReplayPlayback>) {
+    let playback = &mut *playback;
+    if playback.tick >= playback.replay.ticks.len() {
+        return;
+    }
+    playback.tick += 1;
+    if (playback.tick as u32).is_multiple_of(CHECKSUM_INTERVAL) {
+        let index = playback.tick / CHECKSUM_INTERVAL as usize - 1;
+        let expected = playback.replay.checksums.get(index);
+        if playback.desync.is_none() && expected.is_some_and(|&c| c != world.checksum()) {
+            warn!("Replay desynced at tick {}", playback.tick);
+            playback.desync = Some(playback.tick);
+        }
+    }
+}
+
+fn end_playback(playback: Res<ReplayPlayback>, mut next_screen: ResMut<NextState<Screen>>) {
+    if playback.tick >= playback.replay.ticks.len() {
+        info!("Replay finished");
+        next_screen.set(Screen::Title);
+    }
+}
+
+fn finish_playback(playback: Option<ResMut<ReplayPlayback>>) {
+    if let Some(mut playback) = playback {
+        playback.finished = true;
+    }
+}
+
+fn stop_playback(mut commands: Commands) {
+    commands.remove_resource::<ReplayPlayback>();
+}
+
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct ReplayLabel;
+
+fn spawn_replay_panel(mut commands: Commands, playback: Option<Res<ReplayPlayback>>) {
+    if playback.is_none() {
+        return;
+    }
+    commands.spawn((
+        Name::new("Replay Panel"),
+        Node {
+            position_type: PositionType::Absolute,
+            width: percent(100),
+            bottom: px(16),
+            justify_content: JustifyContent::Center,
+            align_items: AlignItems::Center,
+            column_gap: px(10),
+            ..default()
+        },
+        Pickable::IGNORE,
+        DespawnOnExit(Screen::Gameplay),
+        children![
+            (widget::label(""), ReplayLabel),
+            speed_button(PLAYBACK_SPEEDS[0]),
+            speed_button(PLAYBACK_SPEEDS[1]),
+            speed_button(PLAYBACK_SPEEDS[2]),
+            speed_button(PLAYBACK_SPEEDS[3]),
+            speed_button(PLAYBACK_SPEEDS[4]),
+            speed_button(PLAYBACK_SPEEDS[5]),
+            widget::button_medium("Stop", stop_watching),
+        ],
+    ));
+}
+
+fn speed_button(speed: f32) -> impl Bundle {
+    let text = if speed == 0.0 {
+        "Pause".to_string()
+    } else {
+        format!("{speed}x")
+    };
+    widget::button_medium(
+        text,
+        move |_: On<Pointer<Click>>,
+              mut playback: ResMut<ReplayPlayback>,
+              mut time_scale: ResMut<TimeScale>| {
+            playback.speed = speed;
+            time_scale.playback = speed;
+        },
+    )
+}
+
+fn stop_watching(_: On<Pointer<Click>>, mut next_screen: ResMut<NextState<Screen>>) {
+    next_screen.set(Screen::Title);
+}
+
+fn update_replay_label(
+    playback: Res<ReplayPlayback>,
+    mut label_query: Query<&mut Text, With<ReplayLabel>>,
+) {
+    for mut text in &mut label_query {
+        text.0 = match playback.desync {
+            Some(tick) => format!("Replay desynced at tick {tick}"),
+            None if playback.speed == 0.0 => "Replay paused".to_string(),
+            None => format!("Replay {}x", playback.speed),
+        };
+    }
+}
@@ -0,0 +1,73 @@
+//! Seeded randomness, so runs can be reproduced.
+//!
+//! All gameplay randomness goes through [`GameRng`], which is reseeded at the
+//! start of every run. Each system draws from its own named
+//! [`stream`](GameRng::stream), so adding randomness in one place doesn't shift
+//! the numbers drawn everywhere else. The streams are [`ChaCha12Rng`]s, the
+//! same generator as [`StdRng`](rand::rngs::StdRng), but with a stable output
+//! and a state that can be saved along with the run.
+
+use bevy::{platform::collections::HashMap, prelude::*};
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha12Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::screens::Screen;
+
+pub(super) fn plugin(app: &mut App) {
+    app.insert_resource(GameRng::new(rand::rng().random()));
+    app.init_resource::<NextSeed>();
+    app.add_systems(OnEnter(Screen::Gameplay), reseed);
+}
+
+/// The source of all gameplay randomness.
+#[derive(Resource, Reflect, Clone, Debug, Serialize, Deserialize)]
+#[reflect(opaque)]
+#[reflect(Resource, Serialize, Deserialize)]
+pub struct GameRng {
+    seed: u64,
+    streams: HashMap<String, ChaCha12Rng>,
+}
+
+impl GameRng {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            seed,
+            streams: HashMap::default(),
+        }
+    }
+
+    /// The seed of the current run.
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// The random number stream called `name`. Every stream is forked from the
+    /// seed independently, so the numbers it yields only depend on the seed and
+    /// how often the stream itself has been used.
+    pub fn stream(&mut self, name: &'static str) -> &mut ChaCha12Rng {
+        let seed = self.seed;
+        self.streams
+            .entry_ref(name)
+            .or_insert_with(|| ChaCha12Rng::seed_from_u64(seed ^ fnv1a(name)))
+    }
+}
+
+/// The seed the next run should use instead of a random one, e.g. from the
+/// custom run menu.
+#[derive(Resource, Default, Debug)]
+pub struct NextSeed(pub Option<u64>);
+
+fn reseed(mut next_seed: ResMut<NextSeed>, mut rng: ResMut<GameRng>) {
+    let seed = next_seed.0.take().unwrap_or_else(|| rand::rng().random());
+    *rng = GameRng::new(seed);
+    info!("Starting run with seed {seed}");
+}
+
+/// A hash of `name` that stays the same across builds and platforms, unlike
+/// [`std::hash::DefaultHasher`].
+fn fnv1a(name: &str) -> u64 {
+    name.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0000_0100_0000_01b3)
+    })
+}
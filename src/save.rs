@@ -0,0 +1,171 @@
+//! Saving a run in progress and continuing it later.
+//!
+//! "Save & Quit" in the pause menu extracts the run into a [`DynamicScene`]:
+//! the player, its gun and the enemies, along with [`RunStats`] and [`GameRng`].
+//! The scene is serialized with the app's type registry. "Continue" in the main
+//! menu deserializes it into a [`SavedRun`], and once the level has been
+//! spawned, the saved components are applied on top of it. A save can only be
+//! continued once, and one that can't be read is kept aside rather than deleted.
+
+use std::io;
+
+use avian2d::prelude::LinearVelocity;
+use bevy::{
+    prelude::*,
+    reflect::TypeRegistry,
+    scene::{DynamicEntity, serde::SceneDeserializer},
+};
+use serde::de::DeserializeSeed;
+
+use crate::{
+    gameplay::{
+        enemy::{Enemy, enemy},
+        gun::Gun,
+        level::{Level, spawn_level},
+        player::{Health, Player},
+        stats::RunStats,
+    },
+    rng::GameRng,
+    screens::Screen,
+    sprite_atlas::SpriteAtlas,
+    storage,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(
+        OnEnter(Screen::Gameplay),
+        restore_run
+            .after(spawn_level)
+            .run_if(resource_exists::<SavedRun>),
+    );
+}
+
+/// The storage key the saved run is stored under.
+const STORAGE_KEY: &str = "run";
+
+/// Whether there is a saved run to continue.
+pub fn has_saved_run() -> bool {
+    matches!(storage::read(STORAGE_KEY), Ok(Some(_)))
+}
+
+/// Save the run in progress, replacing any previous save.
+///
+/// Run this with [`Commands::run_system_cached`] right before leaving gameplay.
+pub fn save_run(world: &World) {
+    let Some(mut query) =
+        world.try_query_filtered::<Entity, Or<(With<Player>, With<Gun>, With<Enemy>)>>()
+    else {
+        return;
+    };
+    let scene = DynamicSceneBuilder::from_world(world)
+        .deny_all()
+        .allow_component::<Player>()
+        .allow_component::<Health>()
+        .allow_component::<Gun>()
+        .allow_component::<Enemy>()
+        .allow_component::<Transform>()
+        .allow_component::<LinearVelocity>()
+        .allow_resource::<RunStats>()
+        .allow_resource::<GameRng>()
+        .extract_entities(query.iter(world))
+        .extract_resources()
+        .build();
+
+    let registry = world.resource::<AppTypeRegistry>().read();
+    let result = scene
+        .serialize(&registry)
+        .map_err(io::Error::other)
+        .and_then(|serialized| storage::write(STORAGE_KEY, &serialized));
+    match result {
+        Ok(()) => info!("Saved run"),
+        Err(error) => warn!("Failed to save run: {error}"),
+    }
+}
+
+/// Read the saved run and remove it from storage, so it can't be continued twice.
+///
+/// A save that can't be read, e.g. because it was made by another version of the
+/// game, is moved aside to `run.corrupt` instead of being lost.
+pub fn take_saved_run(registry: &TypeRegistry) -> io::Result<SavedRun> {
+    let serialized = storage::read(STORAGE_KEY)?
+        .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no saved run"))?;
+
+    let scene = match deserialize_run(registry, &serialized) {
+        Ok(scene) => scene,
+        Err(error) => {
+            let backup_key = format!("{STORAGE_KEY}.corrupt");
+            storage::write(&backup_key, &serialized)?;
+            storage::remove(STORAGE_KEY)?;
+            return Err(error);
+        }
+    };
+    storage::remove(STORAGE_KEY)?;
+    Ok(SavedRun(scene))
+}
+
+fn deserialize_run(registry: &TypeRegistry, serialized: &str) -> io::Result<DynamicScene> {
+    let mut deserializer = ron::Deserializer::from_str(serialized)
+        .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))?;
+    SceneDeserializer {
+        type_registry: registry,
+    }
+    .deserialize(&mut deserializer)
+    .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))
+}
+
+/// A saved run, restored when the next run starts.
+#[derive(Resource)]
+pub struct SavedRun(DynamicScene);
+
+/// The saved value of type `T` among `values`, if there is one.
+fn saved<T: FromReflect + TypePath>(values: &[Box<dyn PartialReflect>]) -> Option<T> {
+    values
+        .iter()
+        .find(|value| value.represents::<T>())
+        .and_then(|value| T::from_reflect(value.as_ref()))
+}
+
+fn restore_run(
+    mut commands: Commands,
+    saved_run: Res<SavedRun>,
+    sprites: If<Res<SpriteAtlas>>,
+    level: Single<Entity, With<Level>>,
+    mut player: Single<(&mut Transform, &mut Health), With<Player>>,
+    mut gun: Single<&mut Gun>,
+) {
+    let scene = &saved_run.0;
+    for DynamicEntity { components, .. } in &scene.entities {
+        if saved::<Player>(components).is_some() {
+            if let Some(transform) = saved::<Transform>(components) {
+                *player.0 = transform;
+            }
+            if let Some(health) = saved::<Health>(components) {
+                *player.1 = health;
+            }
+        } else if let Some(saved_gun) = saved::<Gun>(components) {
+            **gun = saved_gun;
+        } else if let Some(saved_enemy) = saved::<Enemy>(components) {
+            let transform = saved::<Transform>(components).unwrap_or_default();
+            let velocity = saved::<LinearVelocity>(components).unwrap_or_default();
+            let enemy = commands
+                .spawn(enemy(&sprites, transform.translation.truncate()))
+                .insert((saved_enemy, transform, velocity))
+                .id();
+            commands.entity(*level).add_child(enemy);
+        }
+    }
+
+    // Resources are inserted with commands, so they replace the fresh ones other
+    // systems set up when the run started.
+    if let Some(stats) = saved::<RunStats>(&scene.resources) {
+        commands.insert_resource(RunStats {
+            resumed: true,
+            ..stats
+        });
+    }
+    if let Some(rng) = saved::<GameRng>(&scene.resources) {
+        commands.insert_resource(rng);
+    }
+    commands.remove_resource::<SavedRun>();
+    info!("Continued saved run");
+}
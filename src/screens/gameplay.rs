@@ -1,9 +1,18 @@
 //! The screen state for the main gameplay.
 
 use avian2d::prelude::{Physics, PhysicsTime};
-use bevy::{input::common_conditions::input_just_pressed, prelude::*};
+use bevy::prelude::*;
 
-use crate::{Pause, gameplay::level::spawn_level, menus::Menu, screens::Screen};
+use crate::{
+    Pause,
+    gameplay::{
+        level::spawn_level,
+        player::{Health, Player},
+    },
+    input::{Action, action_just_pressed},
+    menus::Menu,
+    screens::Screen,
+};
 
 pub(super) fn plugin(app: &mut App) {
     app.add_systems(OnEnter(Screen::Gameplay), spawn_level);
@@ -15,15 +24,21 @@ pub(super) fn plugin(app: &mut App) {
             (pause, spawn_pause_overlay, open_pause_menu).run_if(
                 in_state(Screen::Gameplay)
                     .and(in_state(Menu::None))
-                    .and(input_just_pressed(KeyCode::KeyP).or(input_just_pressed(KeyCode::Escape))),
+                    .and(action_just_pressed(Action::Pause).or(action_just_pressed(Action::Back))),
             ),
+            // The controls menu listens for raw input while rebinding, so don't close it.
             close_menu.run_if(
                 in_state(Screen::Gameplay)
                     .and(not(in_state(Menu::None)))
-                    .and(input_just_pressed(KeyCode::KeyP)),
+                    .and(not(in_state(Menu::Controls)))
+                    .and(action_just_pressed(Action::Pause)),
             ),
         ),
     );
+    app.add_systems(
+        Update,
+        end_run.run_if(in_state(Screen::Gameplay).and(player_is_dead)),
+    );
     app.add_systems(OnExit(Screen::Gameplay), (close_menu, unpause));
     app.add_systems(
         OnEnter(Menu::None),
@@ -31,6 +46,14 @@ pub(super) fn plugin(app: &mut App) {
     );
 }
 
+fn player_is_dead(player_query: Query<&Health, With<Player>>) -> bool {
+    player_query.iter().any(|health| health.current <= 0.0)
+}
+
+fn end_run(mut next_screen: ResMut<NextState<Screen>>) {
+    next_screen.set(Screen::GameOver);
+}
+
 fn unpause(mut next_pause: ResMut<NextState<Pause>>, mut physics_time: ResMut<Time<Physics>>) {
     next_pause.set(Pause(false));
     physics_time.unpause();
@@ -0,0 +1,133 @@
+//! The intro cutscene that plays before a new run, zooming into the sleeping
+//! patient's brain where the run takes place.
+//!
+//! The title screen loads the cutscene in the background. If it or the sprite
+//! atlas it uses fails to load, the intro is skipped rather than keeping the
+//! player from the game, and the loading screen reports what went wrong. Once the
+//! intro is over its assets are freed, until the title screen loads them again.
+
+use bevy::prelude::*;
+
+use crate::{
+    asset_tracking::{
+        AssetCollection, AssetGroup, LoadAssetGroup, LoadResource, ResourceHandles,
+        UnloadAssetGroup,
+    },
+    cutscene::{Cutscene, CutsceneFinished, CutscenePlayer},
+    screens::Screen,
+    theme::prelude::*,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.load_resource::<IntroAssets>(AssetGroup::Intro);
+
+    app.add_systems(
+        OnEnter(Screen::Intro),
+        (load_intro_assets, spawn_intro_loading_label),
+    );
+    app.add_systems(OnExit(Screen::Intro), unload_intro_assets);
+    app.add_systems(
+        Update,
+        (
+            spawn_intro
+                .run_if(resource_exists::<IntroAssets>.and(not(any_with_component::<Intro>))),
+            // The cutscene can't be skipped before it starts, so skip the intro
+            // for it if it never will.
+            skip_intro.run_if(intro_failed.and(not(intro_playing))),
+            despawn_intro_loading_label,
+        )
+            .run_if(in_state(Screen::Intro)),
+    );
+}
+
+#[derive(Resource, Asset, Clone, Reflect, AssetCollection)]
+#[reflect(Resource)]
+struct IntroAssets {
+    #[dependency]
+    #[asset(path = "cutscenes/intro.cutscene.ron")]
+    cutscene: Handle<Cutscene>,
+}
+
+/// The intro's [`CutscenePlayer`].
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct Intro;
+
+/// Shown until the cutscene starts.
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct IntroLoadingLabel;
+
+fn load_intro_assets(mut commands: Commands) {
+    commands.queue(LoadAssetGroup(AssetGroup::Intro));
+}
+
+fn spawn_intro_loading_label(mut commands: Commands) {
+    commands.spawn((
+        widget::ui_root("Intro Loading Label"),
+        IntroLoadingLabel,
+        DespawnOnExit(Screen::Intro),
+        children![widget::label("Loading...")],
+    ));
+}
+
+fn despawn_intro_loading_label(
+    mut commands: Commands,
+    intro: Single<&CutscenePlayer, With<Intro>>,
+    label: Single<Entity, With<IntroLoadingLabel>>,
+) {
+    if intro.is_playing() {
+        commands.entity(*label).despawn();
+    }
+}
+
+fn unload_intro_assets(mut commands: Commands) {
+    commands.queue(UnloadAssetGroup(AssetGroup::Intro));
+}
+
+fn spawn_intro(mut commands: Commands, intro_assets: Res<IntroAssets>) {
+    commands
+        .spawn((
+            Name::new("Intro"),
+            Intro,
+            CutscenePlayer::new(intro_assets.cutscene.clone()),
+            DespawnOnExit(Screen::Intro),
+        ))
+        .observe(finish_intro);
+}
+
+fn finish_intro(
+    _: On<CutsceneFinished>,
+    resource_handles: Res<ResourceHandles>,
+    next_screen: ResMut<NextState<Screen>>,
+) {
+    enter_loading_or_gameplay_screen(&resource_handles, next_screen);
+}
+
+fn skip_intro(resource_handles: Res<ResourceHandles>, next_screen: ResMut<NextState<Screen>>) {
+    enter_loading_or_gameplay_screen(&resource_handles, next_screen);
+}
+
+fn enter_loading_or_gameplay_screen(
+    resource_handles: &ResourceHandles,
+    mut next_screen: ResMut<NextState<Screen>>,
+) {
+    if resource_handles.is_done(AssetGroup::Gameplay) {
+        next_screen.set(Screen::Gameplay);
+    } else {
+        next_screen.set(Screen::Loading);
+    }
+}
+
+fn intro_playing(intro_query: Query<&CutscenePlayer, With<Intro>>) -> bool {
+    intro_query.iter().any(CutscenePlayer::is_playing)
+}
+
+/// Whether the cutscene or the sprite atlas it waits for failed to load.
+fn intro_failed(resource_handles: Res<ResourceHandles>) -> bool {
+    resource_handles
+        .failures(AssetGroup::Intro)
+        .chain(resource_handles.failures(AssetGroup::Gameplay))
+        .next()
+        .is_some()
+}
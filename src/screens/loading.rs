@@ -1,31 +1,116 @@
-//! A loading screen during which game assets are loaded if necessary.
-//! This reduces stuttering, especially for audio on Wasm.
+//! A loading screen during which a run's assets are loaded if necessary.
+//! This reduces stuttering, especially for audio on Wasm. The title screen
+//! already loads them in the background, so this usually only shows briefly.
+//!
+//! If an asset fails to load, the screen shows which one instead of waiting
+//! forever.
 
 use bevy::prelude::*;
 
-use crate::{asset_tracking::ResourceHandles, screens::Screen, theme::prelude::*};
+use crate::{
+    asset_tracking::{AssetGroup, LoadAssetGroup, ResourceHandles},
+    screens::Screen,
+    theme::{palette::LOADING_FILL, prelude::*},
+};
 
 pub(super) fn plugin(app: &mut App) {
-    app.add_systems(OnEnter(Screen::Loading), spawn_loading_screen);
+    app.add_systems(
+        OnEnter(Screen::Loading),
+        (load_gameplay_assets, spawn_loading_screen),
+    );
 
     app.add_systems(
         Update,
-        enter_gameplay_screen.run_if(in_state(Screen::Loading).and(all_assets_loaded)),
+        (
+            update_loading_progress,
+            show_loading_errors.run_if(loading_failed),
+            enter_gameplay_screen.run_if(all_assets_loaded),
+        )
+            .run_if(in_state(Screen::Loading)),
     );
 }
 
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct LoadingScreen;
+
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct LoadingProgressBar;
+
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct LoadingProgressLabel;
+
+fn load_gameplay_assets(mut commands: Commands) {
+    commands.queue(LoadAssetGroup(AssetGroup::Gameplay));
+}
+
 fn spawn_loading_screen(mut commands: Commands) {
     commands.spawn((
         widget::ui_root("Loading Screen"),
+        LoadingScreen,
         DespawnOnExit(Screen::Loading),
-        children![widget::label("Loading...")],
+        children![
+            widget::label("Loading..."),
+            (
+                widget::progress_bar(400.0, LOADING_FILL),
+                LoadingProgressBar
+            ),
+            (widget::label(""), LoadingProgressLabel),
+        ],
     ));
 }
 
+fn update_loading_progress(
+    resource_handles: Res<ResourceHandles>,
+    mut bar: Single<&mut ProgressBar, With<LoadingProgressBar>>,
+    mut label: Single<&mut Text, With<LoadingProgressLabel>>,
+) {
+    let (loaded, total) = resource_handles.progress(AssetGroup::Gameplay);
+    bar.set_if_neq(ProgressBar(resource_handles.fraction(AssetGroup::Gameplay)));
+    label.0 = format!("{loaded} / {total}");
+}
+
+/// Replace the loading screen with the assets that failed to load.
+fn show_loading_errors(
+    mut commands: Commands,
+    resource_handles: Res<ResourceHandles>,
+    loading_screen: Single<Entity, With<LoadingScreen>>,
+) {
+    commands.entity(*loading_screen).despawn();
+    commands
+        .spawn((
+            widget::ui_root("Loading Error Screen"),
+            DespawnOnExit(Screen::Loading),
+        ))
+        .with_children(|parent| {
+            parent.spawn(widget::header("Failed to load assets"));
+            for failure in resource_handles.failures(AssetGroup::Gameplay) {
+                parent.spawn(widget::label(format!(
+                    "{}: {}",
+                    failure.path, failure.error
+                )));
+            }
+            parent.spawn(widget::button("Back", go_back));
+        });
+}
+
+fn go_back(_: On<Pointer<Click>>, mut next_screen: ResMut<NextState<Screen>>) {
+    next_screen.set(Screen::Title);
+}
+
 fn enter_gameplay_screen(mut next_screen: ResMut<NextState<Screen>>) {
     next_screen.set(Screen::Gameplay);
 }
 
 fn all_assets_loaded(resource_handles: Res<ResourceHandles>) -> bool {
-    resource_handles.is_all_done()
+    resource_handles.is_done(AssetGroup::Gameplay)
+}
+
+fn loading_failed(resource_handles: Res<ResourceHandles>) -> bool {
+    resource_handles
+        .failures(AssetGroup::Gameplay)
+        .next()
+        .is_some()
 }
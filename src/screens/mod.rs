@@ -1,6 +1,8 @@
 //! The game's main screen states and transitions between them.
 
+mod game_over;
 mod gameplay;
+mod intro;
 mod loading;
 mod splash;
 mod title;
@@ -11,7 +13,9 @@ pub(super) fn plugin(app: &mut App) {
     app.init_state::<Screen>();
 
     app.add_plugins((
+        game_over::plugin,
         gameplay::plugin,
+        intro::plugin,
         loading::plugin,
         splash::plugin,
         title::plugin,
@@ -24,6 +28,8 @@ pub enum Screen {
     #[default]
     Splash,
     Title,
+    Intro,
     Loading,
     Gameplay,
+    GameOver,
 }
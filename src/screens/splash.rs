@@ -2,17 +2,25 @@
 
 use bevy::{
     image::{ImageLoaderSettings, ImageSampler},
-    input::common_conditions::input_just_pressed,
     prelude::*,
 };
 
-use crate::{AppSystems, screens::Screen, theme::prelude::*};
+use crate::{
+    AppSystems,
+    asset_tracking::{AssetGroup, LoadAssetGroup},
+    input::{Action, action_just_pressed},
+    screens::Screen,
+    theme::prelude::*,
+};
 
 pub(super) fn plugin(app: &mut App) {
     // Spawn splash screen.
     app.insert_resource(ClearColor(SPLASH_BACKGROUND_COLOR));
     app.add_systems(OnEnter(Screen::Splash), spawn_splash_screen);
 
+    // Load the menus' assets while the splash screen plays.
+    app.add_systems(OnEnter(Screen::Splash), load_title_assets);
+
     // Animate splash screen.
     app.add_systems(
         Update,
@@ -38,8 +46,7 @@ pub(super) fn plugin(app: &mut App) {
     // Exit the splash screen early if the player hits escape.
     app.add_systems(
         Update,
-        enter_title_screen
-            .run_if(input_just_pressed(KeyCode::Escape).and(in_state(Screen::Splash))),
+        enter_title_screen.run_if(action_just_pressed(Action::Back).and(in_state(Screen::Splash))),
     );
 }
 
@@ -143,3 +150,7 @@ fn check_splash_timer(timer: ResMut<SplashTimer>, mut next_screen: ResMut<NextSt
 fn enter_title_screen(mut next_screen: ResMut<NextState<Screen>>) {
     next_screen.set(Screen::Title);
 }
+
+fn load_title_assets(mut commands: Commands) {
+    commands.queue(LoadAssetGroup(AssetGroup::Title));
+}
@@ -2,13 +2,26 @@
 
 use bevy::prelude::*;
 
-use crate::{menus::Menu, screens::Screen};
+use crate::{
+    asset_tracking::{AssetGroup, LoadAssetGroup},
+    menus::Menu,
+    screens::Screen,
+};
 
 pub(super) fn plugin(app: &mut App) {
-    app.add_systems(OnEnter(Screen::Title), open_main_menu);
+    app.add_systems(OnEnter(Screen::Title), (load_assets, open_main_menu));
     app.add_systems(OnExit(Screen::Title), close_menu);
 }
 
+/// Load the menus' assets if the splash screen was skipped, and start loading
+/// the intro's and a run's assets in the background so pressing "Play" rarely
+/// has to wait.
+fn load_assets(mut commands: Commands) {
+    commands.queue(LoadAssetGroup(AssetGroup::Title));
+    commands.queue(LoadAssetGroup(AssetGroup::Intro));
+    commands.queue(LoadAssetGroup(AssetGroup::Gameplay));
+}
+
 fn open_main_menu(mut next_menu: ResMut<NextState<Menu>>) {
     next_menu.set(Menu::Main);
 }
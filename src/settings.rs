@@ -0,0 +1,242 @@
+//! Player settings that persist between sessions.
+//!
+//! [`Settings`] is loaded from [`storage`](crate::storage) while the app is being
+//! built, so everything is in place before the splash screen. Volumes and key
+//! bindings are owned by their own resources ([`AudioVolumes`], [`InputMap`]) and
+//! mirrored into [`Settings`] whenever they change. Any change to [`Settings`] is
+//! written back to storage.
+
+use bevy::{
+    prelude::*,
+    window::{MonitorSelection, PrimaryWindow, WindowMode},
+};
+use ron::extensions::Extensions;
+use serde::{Deserialize, Serialize};
+
+use crate::{audio::AudioVolumes, input::InputMap, storage};
+
+pub(super) fn plugin(app: &mut App) {
+    let settings = load_settings();
+    app.insert_resource(settings.volumes.clone());
+    app.insert_resource(settings.bindings.clone());
+    app.insert_resource(settings);
+
+    app.add_systems(
+        Update,
+        (
+            record_volumes.run_if(resource_changed::<AudioVolumes>),
+            record_bindings.run_if(resource_changed::<InputMap>),
+            apply_window_mode.run_if(resource_changed::<Settings>),
+            save_settings.run_if(resource_changed::<Settings>.and(not(resource_added::<Settings>))),
+        )
+            .chain(),
+    );
+}
+
+/// The storage key settings are saved under.
+const STORAGE_KEY: &str = "settings";
+
+/// The current version of the settings schema. Bump this when making a change that
+/// can't be handled by `#[serde(default)]` alone, and migrate old versions in
+/// [`Settings::migrate`].
+const SETTINGS_VERSION: u32 = 2;
+
+#[derive(Resource, Clone, PartialEq, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    /// The schema version these settings were saved with.
+    pub version: u32,
+    pub volumes: AudioVolumes,
+    /// Linear master volume, only used by version 1.
+    #[serde(skip_serializing)]
+    master_volume: Option<f32>,
+    pub bindings: InputMap,
+    pub accessibility: Accessibility,
+    pub effects: Effects,
+    pub display_mode: DisplayMode,
+    pub language: Language,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            version: SETTINGS_VERSION,
+            volumes: AudioVolumes::default(),
+            master_volume: None,
+            bindings: InputMap::default(),
+            accessibility: Accessibility::default(),
+            effects: Effects::default(),
+            display_mode: DisplayMode::default(),
+            language: Language::default(),
+        }
+    }
+}
+
+impl Settings {
+    /// Bring settings saved by an older version of the game up to date.
+    fn migrate(mut self) -> Self {
+        if self.version < 2 {
+            // Version 1 only had a master volume.
+            if let Some(master_volume) = self.master_volume.take() {
+                self.volumes.master.volume = master_volume;
+            }
+        }
+        self.version = SETTINGS_VERSION;
+        self.bindings.fill_missing_actions();
+        self
+    }
+}
+
+#[derive(Clone, PartialEq, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Accessibility {
+    /// Tone down bright flashes, e.g. when enemies are hit.
+    pub reduce_flashing: bool,
+}
+
+/// Optional visual feedback during gameplay.
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Effects {
+    /// Show floating damage numbers where enemies are hit.
+    pub damage_numbers: bool,
+    /// Briefly flash enemies when they are hit.
+    pub hit_flash: bool,
+}
+
+impl Default for Effects {
+    fn default() -> Self {
+        Self {
+            damage_numbers: true,
+            hit_flash: true,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Default, Serialize, Deserialize)]
+pub enum DisplayMode {
+    #[default]
+    Windowed,
+    Fullscreen,
+}
+
+impl DisplayMode {
+    fn window_mode(self) -> WindowMode {
+        match self {
+            Self::Windowed => WindowMode::Windowed,
+            Self::Fullscreen => WindowMode::BorderlessFullscreen(MonitorSelection::Current),
+        }
+    }
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Default, Serialize, Deserialize)]
+pub enum Language {
+    #[default]
+    English,
+}
+
+/// Load settings from storage, falling back to the defaults if they are missing,
+/// unreadable or from a newer version of the game.
+fn load_settings() -> Settings {
+    let contents = match storage::read(STORAGE_KEY) {
+        Ok(Some(contents)) => contents,
+        Ok(None) => return Settings::default(),
+        Err(error) => {
+            warn!("Failed to read settings, using defaults: {error}");
+            return Settings::default();
+        }
+    };
+
+    match parse_settings(&contents) {
+        Ok(settings) if settings.version > SETTINGS_VERSION => {
+            warn!(
+                "Settings were saved by a newer version of the game (v{}), using defaults",
+                settings.version
+            );
+            Settings::default()
+        }
+        Ok(settings) => settings.migrate(),
+        Err(error) => {
+            warn!("Settings are corrupt, using defaults: {error}");
+            // Keep a copy around so the player's settings aren't silently lost.
+            let backup_key = format!("{STORAGE_KEY}.corrupt");
+            if let Err(error) = storage::write(&backup_key, &contents) {
+                warn!("Failed to back up corrupt settings: {error}");
+            }
+            Settings::default()
+        }
+    }
+}
+
+/// Parse saved settings. Version 1 wrote its master volume as a plain number,
+/// so `Option` fields accept values without `Some(...)` around them.
+fn parse_settings(contents: &str) -> ron::error::SpannedResult<Settings> {
+    ron::Options::default()
+        .with_default_extension(Extensions::IMPLICIT_SOME)
+        .from_str(contents)
+}
+
+fn save_settings(settings: Res<Settings>) {
+    let contents = match ron::ser::to_string_pretty(&*settings, default()) {
+        Ok(contents) => contents,
+        Err(error) => {
+            error!("Failed to serialize settings: {error}");
+            return;
+        }
+    };
+    if let Err(error) = storage::write(STORAGE_KEY, &contents) {
+        warn!("Failed to save settings: {error}");
+    }
+}
+
+fn record_volumes(volumes: Res<AudioVolumes>, mut settings: ResMut<Settings>) {
+    if settings.volumes != *volumes {
+        settings.volumes = volumes.clone();
+    }
+}
+
+fn record_bindings(input_map: Res<InputMap>, mut settings: ResMut<Settings>) {
+    if settings.bindings != *input_map {
+        settings.bindings = input_map.clone();
+    }
+}
+
+fn apply_window_mode(
+    settings: Res<Settings>,
+    mut window: Single<&mut Window, With<PrimaryWindow>>,
+) {
+    let mode = settings.display_mode.window_mode();
+    if window.mode != mode {
+        window.mode = mode;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::input::{Action, Binding};
+
+    #[test]
+    fn migrates_version_1() {
+        let contents = r#"(
+            version: 1,
+            master_volume: 0.25,
+            bindings: (bindings: {Fire: [Key(Space)]}),
+            accessibility: (reduce_flashing: true),
+            display_mode: Fullscreen,
+            language: English,
+        )"#;
+        let settings = parse_settings(contents).unwrap().migrate();
+
+        assert_eq!(settings.version, SETTINGS_VERSION);
+        assert_eq!(settings.volumes.master.volume, 0.25);
+        assert_eq!(
+            settings.bindings.bindings(Action::Fire),
+            [Binding::Key(KeyCode::Space)]
+        );
+        // Actions added since version 1 get their default bindings.
+        assert!(!settings.bindings.bindings(Action::Dash).is_empty());
+        assert!(settings.accessibility.reduce_flashing);
+        assert_eq!(settings.display_mode, DisplayMode::Fullscreen);
+    }
+}
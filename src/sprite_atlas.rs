@@ -0,0 +1,254 @@
+//! Sprites packed into a single texture atlas when the game loads.
+//!
+//! The loose sprite images are listed in a `.atlas.ron` file next to them, which
+//! names each sprite, describes how its image is split into frames and defines
+//! animation clips over those frames. Loading that file packs every frame into
+//! one [`SpriteAtlas`], so sprites are looked up by name instead of by image and
+//! hand-written grid index, and all of them batch into the same draw call.
+//!
+//! ```ron
+//! (
+//!     sprites: {
+//!         "ducky": (path: "ducky.png", tile_size: 32, columns: 6, rows: 2, padding: 1),
+//!     },
+//!     clips: {
+//!         "ducky_walk": (sprite: "ducky", first: 6, count: 6, frame_millis: 50),
+//!     },
+//! )
+//! ```
+
+use std::{collections::BTreeMap, ops::Range, time::Duration};
+
+use bevy::{
+    asset::{AssetLoader, LoadContext, io::Reader},
+    image::{ImageSampler, TextureAtlasBuilder},
+    platform::collections::HashMap,
+    prelude::*,
+};
+use serde::Deserialize;
+
+use crate::asset_tracking::{AssetGroup, LoadResource};
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_asset_loader::<SpriteAtlasLoader>();
+    app.load_resource_from_path::<SpriteAtlas>("images/sprites.atlas.ron", AssetGroup::Gameplay);
+}
+
+/// Every sprite of the game, packed into one texture atlas.
+#[derive(Resource, Asset, Clone, Reflect)]
+#[reflect(Resource)]
+pub struct SpriteAtlas {
+    #[dependency]
+    image: Handle<Image>,
+    #[dependency]
+    layout: Handle<TextureAtlasLayout>,
+    /// The atlas indices of each sprite's frames.
+    sprites: HashMap<String, Range<usize>>,
+    clips: HashMap<String, SpriteClip>,
+}
+
+impl SpriteAtlas {
+    /// The atlas indices of the frames of the sprite called `name`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the atlas has no sprite called `name`.
+    pub fn frames(&self, name: &str) -> Range<usize> {
+        self.sprites
+            .get(name)
+            .unwrap_or_else(|| panic!("the sprite atlas has no sprite called {name:?}"))
+            .clone()
+    }
+
+    /// The first frame of the sprite called `name`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the atlas has no sprite called `name`.
+    pub fn sprite(&self, name: &str) -> Sprite {
+        Sprite::from_atlas_image(
+            self.image.clone(),
+            TextureAtlas {
+                layout: self.layout.clone(),
+                index: self.frames(name).start,
+            },
+        )
+    }
+
+    /// The `frame`th frame of the sprite called `name`, or `None` if the atlas
+    /// has no such sprite or frame.
+    pub fn frame(&self, name: &str, frame: usize) -> Option<Sprite> {
+        let index = self.sprites.get(name)?.clone().nth(frame)?;
+        Some(Sprite::from_atlas_image(
+            self.image.clone(),
+            TextureAtlas {
+                layout: self.layout.clone(),
+                index,
+            },
+        ))
+    }
+
+    /// The animation clip called `name`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the atlas has no clip called `name`.
+    pub fn clip(&self, name: &str) -> &SpriteClip {
+        self.clips
+            .get(name)
+            .unwrap_or_else(|| panic!("the sprite atlas has no clip called {name:?}"))
+    }
+}
+
+/// A run of frames of one sprite, shown one after the other.
+#[derive(Clone, Debug, Reflect)]
+pub struct SpriteClip {
+    /// The atlas indices of the frames, in order.
+    pub frames: Range<usize>,
+    /// How long each frame is shown.
+    pub frame_duration: Duration,
+}
+
+impl SpriteClip {
+    pub fn frame_count(&self) -> usize {
+        self.frames.len()
+    }
+
+    /// The atlas index of the clip's `frame`th frame, wrapping around at the end.
+    pub fn index(&self, frame: usize) -> usize {
+        self.frames.start + frame % self.frame_count().max(1)
+    }
+}
+
+/// The contents of a `.atlas.ron` file.
+#[derive(Deserialize)]
+struct AtlasMeta {
+    // Sorted, so sprites get the same atlas indices every time.
+    sprites: BTreeMap<String, SpriteMeta>,
+    #[serde(default)]
+    clips: BTreeMap<String, ClipMeta>,
+}
+
+/// A sprite image, split into a grid of frames that are numbered row by row.
+#[derive(Deserialize)]
+struct SpriteMeta {
+    /// The image's path, relative to the `.atlas.ron` file.
+    path: String,
+    /// The size of each square frame, in pixels. Defaults to the image size
+    /// divided by `columns` and `rows`.
+    tile_size: Option<u32>,
+    #[serde(default = "one")]
+    columns: u32,
+    #[serde(default = "one")]
+    rows: u32,
+    /// The gap between frames, in pixels.
+    #[serde(default)]
+    padding: u32,
+}
+
+fn one() -> u32 {
+    1
+}
+
+#[derive(Deserialize)]
+struct ClipMeta {
+    sprite: String,
+    /// The sprite frame the clip starts at.
+    #[serde(default)]
+    first: usize,
+    /// How many frames the clip has. Defaults to the rest of the sprite.
+    count: Option<usize>,
+    frame_millis: u64,
+}
+
+/// Space left between packed sprites, so frames don't bleed into each other.
+const PACKING_PADDING: UVec2 = UVec2::ONE;
+
+#[derive(Default, TypePath)]
+struct SpriteAtlasLoader;
+
+impl AssetLoader for SpriteAtlasLoader {
+    type Asset = SpriteAtlas;
+    type Settings = ();
+    type Error = BevyError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        load_context: &mut LoadContext<'_>,
+    ) -> Result<SpriteAtlas, BevyError> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        let meta: AtlasMeta = ron::de::from_bytes(&bytes)?;
+
+        let mut images = Vec::with_capacity(meta.sprites.len());
+        for sprite in meta.sprites.values() {
+            let path = load_context.path().resolve_embed(&sprite.path)?;
+            let image = load_context
+                .loader()
+                .immediate()
+                .load::<Image>(path)
+                .await?;
+            images.push(image.take());
+        }
+
+        let mut builder = TextureAtlasBuilder::default();
+        builder.padding(PACKING_PADDING);
+        for image in &images {
+            builder.add_texture(None, image);
+        }
+        let (packed, _, mut image) = builder.build()?;
+        image.sampler = ImageSampler::nearest();
+
+        // The builder places whole sprite images, so split each one into frames.
+        let mut layout = TextureAtlasLayout::new_empty(packed.size);
+        let mut sprites = HashMap::default();
+        for ((name, sprite), (placed, sprite_image)) in
+            meta.sprites.iter().zip(packed.textures.iter().zip(&images))
+        {
+            let grid = UVec2::new(sprite.columns, sprite.rows);
+            let tile_size = sprite
+                .tile_size
+                .map_or_else(|| sprite_image.size() / grid, UVec2::splat);
+            let first = layout.len();
+            for row in 0..sprite.rows {
+                for column in 0..sprite.columns {
+                    let min = placed.min + UVec2::new(column, row) * (tile_size + sprite.padding);
+                    layout.add_texture(URect::from_corners(min, min + tile_size));
+                }
+            }
+            sprites.insert(name.clone(), first..layout.len());
+        }
+
+        let mut clips = HashMap::default();
+        for (name, clip) in meta.clips {
+            let Some(frames) = sprites.get(&clip.sprite) else {
+                return Err(format!("clip {name:?} uses unknown sprite {:?}", clip.sprite).into());
+            };
+            let start = frames.start + clip.first;
+            let end = clip.count.map_or(frames.end, |count| start + count);
+            if start >= end || end > frames.end {
+                return Err(format!("clip {name:?} has frames outside {:?}", clip.sprite).into());
+            }
+            clips.insert(
+                name,
+                SpriteClip {
+                    frames: start..end,
+                    frame_duration: Duration::from_millis(clip.frame_millis),
+                },
+            );
+        }
+
+        Ok(SpriteAtlas {
+            image: load_context.add_labeled_asset("image".to_string(), image),
+            layout: load_context.add_labeled_asset("layout".to_string(), layout),
+            sprites,
+            clips,
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["atlas.ron"]
+    }
+}
@@ -0,0 +1,169 @@
+//! Small key-value persistence for settings and other player data.
+//!
+//! On native, each key is a RON file in the platform config directory
+//! (e.g. `~/.config/bevyjam7/settings.ron`), or a `.bin` file for binary data.
+//! On the web, it's an entry in `localStorage`, with binary data base64-encoded.
+//! Headless runs call [`use_memory`] so they never touch the player's data.
+
+use std::{
+    collections::HashMap,
+    io,
+    sync::{Mutex, PoisonError},
+};
+
+/// Values stored in memory instead, if [`use_memory`] has been called.
+static MEMORY: Mutex<Option<HashMap<String, Vec<u8>>>> = Mutex::new(None);
+
+/// Keep everything stored from now on in memory, for the rest of the process.
+pub fn use_memory() {
+    MEMORY
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .get_or_insert_default();
+}
+
+/// Run `f` on the in-memory storage, or return `None` if it isn't in use.
+fn with_memory<T>(f: impl FnOnce(&mut HashMap<String, Vec<u8>>) -> T) -> Option<T> {
+    MEMORY
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .as_mut()
+        .map(f)
+}
+
+/// Read the value stored under `key`, or `None` if nothing has been stored yet.
+pub fn read(key: &str) -> io::Result<Option<String>> {
+    match with_memory(|memory| memory.get(key).cloned()) {
+        Some(contents) => contents
+            .map(String::from_utf8)
+            .transpose()
+            .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error)),
+        None => platform::read(key),
+    }
+}
+
+/// Store `contents` under `key`, replacing any previous value.
+pub fn write(key: &str, contents: &str) -> io::Result<()> {
+    with_memory(|memory| memory.insert(key.to_string(), contents.as_bytes().to_vec()))
+        .map_or_else(|| platform::write(key, contents), |_| Ok(()))
+}
+
+/// Remove whatever is stored under `key`, if anything.
+pub fn remove(key: &str) -> io::Result<()> {
+    with_memory(|memory| memory.remove(key)).map_or_else(|| platform::remove(key), |_| Ok(()))
+}
+
+/// Read the binary data stored under `key`, or `None` if nothing has been stored yet.
+pub fn read_bytes(key: &str) -> io::Result<Option<Vec<u8>>> {
+    with_memory(|memory| memory.get(key).cloned()).map_or_else(|| platform::read_bytes(key), Ok)
+}
+
+/// Store binary `contents` under `key`, replacing any previous value.
+pub fn write_bytes(key: &str, contents: &[u8]) -> io::Result<()> {
+    with_memory(|memory| memory.insert(key.to_string(), contents.to_vec()))
+        .map_or_else(|| platform::write_bytes(key, contents), |_| Ok(()))
+}
+
+#[cfg(not(target_family = "wasm"))]
+mod platform {
+    use std::{fs, io, path::PathBuf};
+
+    fn path(key: &str, extension: &str) -> io::Result<PathBuf> {
+        let dir = dirs::config_dir()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no config directory"))?;
+        Ok(dir
+            .join(env!("CARGO_PKG_NAME"))
+            .join(format!("{key}.{extension}")))
+    }
+
+    fn ignore_not_found<T>(result: io::Result<T>) -> io::Result<Option<T>> {
+        match result {
+            Ok(contents) => Ok(Some(contents)),
+            Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(error) => Err(error),
+        }
+    }
+
+    fn write_file(path: PathBuf, contents: &[u8]) -> io::Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, contents)
+    }
+
+    pub fn read(key: &str) -> io::Result<Option<String>> {
+        ignore_not_found(fs::read_to_string(path(key, "ron")?))
+    }
+
+    pub fn write(key: &str, contents: &str) -> io::Result<()> {
+        write_file(path(key, "ron")?, contents.as_bytes())
+    }
+
+    pub fn remove(key: &str) -> io::Result<()> {
+        ignore_not_found(fs::remove_file(path(key, "ron")?)).map(drop)
+    }
+
+    pub fn read_bytes(key: &str) -> io::Result<Option<Vec<u8>>> {
+        ignore_not_found(fs::read(path(key, "bin")?))
+    }
+
+    pub fn write_bytes(key: &str, contents: &[u8]) -> io::Result<()> {
+        write_file(path(key, "bin")?, contents)
+    }
+}
+
+#[cfg(target_family = "wasm")]
+mod platform {
+    use std::io;
+
+    fn local_storage() -> io::Result<web_sys::Storage> {
+        web_sys::window()
+            .and_then(|window| window.local_storage().ok().flatten())
+            .ok_or_else(|| io::Error::new(io::ErrorKind::Unsupported, "localStorage unavailable"))
+    }
+
+    fn item_key(key: &str) -> String {
+        format!("{}.{key}", env!("CARGO_PKG_NAME"))
+    }
+
+    pub fn read(key: &str) -> io::Result<Option<String>> {
+        local_storage()?
+            .get_item(&item_key(key))
+            .map_err(|_| io::Error::other("failed to read from localStorage"))
+    }
+
+    pub fn write(key: &str, contents: &str) -> io::Result<()> {
+        local_storage()?
+            .set_item(&item_key(key), contents)
+            .map_err(|_| io::Error::other("failed to write to localStorage"))
+    }
+
+    pub fn remove(key: &str) -> io::Result<()> {
+        local_storage()?
+            .remove_item(&item_key(key))
+            .map_err(|_| io::Error::other("failed to remove from localStorage"))
+    }
+
+    fn window() -> io::Result<web_sys::Window> {
+        web_sys::window().ok_or_else(|| io::Error::new(io::ErrorKind::Unsupported, "no window"))
+    }
+
+    pub fn read_bytes(key: &str) -> io::Result<Option<Vec<u8>>> {
+        let Some(encoded) = read(key)? else {
+            return Ok(None);
+        };
+        // `atob` decodes to a string with one character per byte.
+        let decoded = window()?
+            .atob(&encoded)
+            .map_err(|_| io::Error::new(io::ErrorKind::InvalidData, "invalid base64"))?;
+        Ok(Some(decoded.chars().map(|c| c as u8).collect()))
+    }
+
+    pub fn write_bytes(key: &str, contents: &[u8]) -> io::Result<()> {
+        let binary: String = contents.iter().map(|&byte| char::from(byte)).collect();
+        let encoded = window()?
+            .btoa(&binary)
+            .map_err(|_| io::Error::other("failed to encode base64"))?;
+        write(key, &encoded)
+    }
+}
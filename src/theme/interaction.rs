@@ -1,6 +1,9 @@
 use bevy::prelude::*;
 
-use crate::{asset_tracking::LoadResource, audio::sound_effect};
+use crate::{
+    asset_tracking::{AssetCollection, AssetGroup, LoadResource},
+    audio::ui_sound,
+};
 
 pub(super) fn plugin(app: &mut App) {
     app.add_observer(apply_interaction_palette_on_click);
@@ -8,7 +11,7 @@ pub(super) fn plugin(app: &mut App) {
     app.add_observer(apply_interaction_palette_on_out);
     app.add_observer(apply_interaction_palette_on_release);
 
-    app.load_resource::<InteractionAssets>();
+    app.load_resource::<InteractionAssets>(AssetGroup::Title);
     app.add_observer(play_sound_effect_on_click);
     app.add_observer(play_sound_effect_on_over);
 }
@@ -68,31 +71,23 @@ fn apply_interaction_palette_on_out(
     *bg = palette.none.into();
 }
 
-#[derive(Resource, Asset, Clone, Reflect)]
+#[derive(Resource, Asset, Clone, Reflect, AssetCollection)]
 #[reflect(Resource)]
 struct InteractionAssets {
     #[dependency]
+    #[asset(path = "audio/sound_effects/button_hover.ogg")]
     hover: Handle<AudioSource>,
     #[dependency]
+    #[asset(path = "audio/sound_effects/button_click.ogg")]
     click: Handle<AudioSource>,
 }
 
-impl FromWorld for InteractionAssets {
-    fn from_world(world: &mut World) -> Self {
-        let assets = world.resource::<AssetServer>();
-        Self {
-            hover: assets.load("audio/sound_effects/button_hover.ogg"),
-            click: assets.load("audio/sound_effects/button_click.ogg"),
-        }
-    }
-}
-
 fn play_sound_effect_on_click(
     _: On<Pointer<Click>>,
     interaction_assets: If<Res<InteractionAssets>>,
     mut commands: Commands,
 ) {
-    commands.spawn(sound_effect(interaction_assets.click.clone()));
+    commands.spawn(ui_sound(interaction_assets.click.clone()));
 }
 
 fn play_sound_effect_on_over(
@@ -100,5 +95,5 @@ fn play_sound_effect_on_over(
     interaction_assets: If<Res<InteractionAssets>>,
     mut commands: Commands,
 ) {
-    commands.spawn(sound_effect(interaction_assets.hover.clone()));
+    commands.spawn(ui_sound(interaction_assets.hover.clone()));
 }
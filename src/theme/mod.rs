@@ -9,11 +9,15 @@ pub mod widget;
 
 #[allow(unused_imports)]
 pub mod prelude {
-    pub use super::{interaction::InteractionPalette, palette as ui_palette, widget};
+    pub use super::{
+        interaction::InteractionPalette,
+        palette as ui_palette,
+        widget::{self, Pips, ProgressBar, RadialProgress},
+    };
 }
 
 use bevy::prelude::*;
 
 pub(super) fn plugin(app: &mut App) {
-    app.add_plugins(interaction::plugin);
+    app.add_plugins((interaction::plugin, widget::plugin));
 }
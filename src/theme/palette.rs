@@ -14,3 +14,14 @@ pub const BUTTON_BACKGROUND: Color = Color::srgb(0.275, 0.400, 0.750);
 pub const BUTTON_HOVERED_BACKGROUND: Color = Color::srgb(0.384, 0.600, 0.820);
 /// #3d4999
 pub const BUTTON_PRESSED_BACKGROUND: Color = Color::srgb(0.239, 0.286, 0.600);
+
+/// #2b2b3d
+pub const HUD_BACKGROUND: Color = Color::srgb(0.169, 0.169, 0.239);
+/// #d94f4f
+pub const HEALTH_FILL: Color = Color::srgb(0.851, 0.310, 0.310);
+/// #fcfbcc
+pub const PIP_FILLED: Color = Color::srgb(0.988, 0.984, 0.800);
+/// #4a4a5e
+pub const PIP_EMPTY: Color = Color::srgb(0.290, 0.290, 0.369);
+/// #4fa36b
+pub const LOADING_FILL: Color = Color::srgb(0.310, 0.639, 0.420);
@@ -1,14 +1,24 @@
 //! Helper functions for creating common widgets.
 
-use std::borrow::Cow;
+use std::{borrow::Cow, f32::consts::TAU};
 
 use bevy::{
-    ecs::{spawn::SpawnWith, system::IntoObserverSystem},
+    ecs::{
+        spawn::{SpawnIter, SpawnWith},
+        system::IntoObserverSystem,
+    },
     prelude::*,
 };
 
 use crate::theme::{interaction::InteractionPalette, palette::*};
 
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(
+        Update,
+        (update_progress_bars, update_pips, update_radial_progress),
+    );
+}
+
 /// A root UI node that fills the window and centers its content.
 pub fn ui_root(name: impl Into<Cow<'static, str>>) -> impl Bundle {
     (
@@ -66,6 +76,28 @@ where
             border_radius: BorderRadius::MAX,
             ..default()
         },
+        40.0,
+    )
+}
+
+/// A medium-sized button with text and an action defined as an [`Observer`].
+pub fn button_medium<E, B, M, I>(text: impl Into<String>, action: I) -> impl Bundle
+where
+    E: EntityEvent,
+    B: Bundle,
+    I: IntoObserverSystem<E, B, M>,
+{
+    button_base(
+        text,
+        action,
+        Node {
+            width: px(180),
+            height: px(32),
+            align_items: AlignItems::Center,
+            justify_content: JustifyContent::Center,
+            ..default()
+        },
+        24.0,
     )
 }
 
@@ -86,6 +118,7 @@ where
             justify_content: JustifyContent::Center,
             ..default()
         },
+        40.0,
     )
 }
 
@@ -94,6 +127,7 @@ fn button_base<E, B, M, I>(
     text: impl Into<String>,
     action: I,
     button_bundle: impl Bundle,
+    font_size: f32,
 ) -> impl Bundle
 where
     E: EntityEvent,
@@ -105,7 +139,7 @@ where
     (
         Name::new("Button"),
         Node::default(),
-        Children::spawn(SpawnWith(|parent: &mut ChildSpawner| {
+        Children::spawn(SpawnWith(move |parent: &mut ChildSpawner| {
             parent
                 .spawn((
                     Name::new("Button Inner"),
@@ -119,7 +153,7 @@ where
                     children![(
                         Name::new("Button Text"),
                         Text(text),
-                        TextFont::from_font_size(40.0),
+                        TextFont::from_font_size(font_size),
                         TextColor(BUTTON_TEXT),
                         // Don't bubble picking events from the text up to the button.
                         Pickable::IGNORE,
@@ -130,3 +164,164 @@ where
         })),
     )
 }
+
+/// A horizontal bar that is filled according to its [`ProgressBar`].
+pub fn progress_bar(width: f32, fill: Color) -> impl Bundle {
+    (
+        Name::new("Progress Bar"),
+        Node {
+            width: px(width),
+            height: px(16),
+            ..default()
+        },
+        BackgroundColor(HUD_BACKGROUND),
+        ProgressBar(1.0),
+        children![(
+            Name::new("Progress Bar Fill"),
+            ProgressBarFill,
+            Node {
+                width: percent(100),
+                height: percent(100),
+                ..default()
+            },
+            BackgroundColor(fill),
+        )],
+    )
+}
+
+/// How full a [`progress_bar`] is, from 0.0 to 1.0.
+#[derive(Component, Reflect, PartialEq)]
+#[reflect(Component)]
+pub struct ProgressBar(pub f32);
+
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct ProgressBarFill;
+
+fn update_progress_bars(
+    bar_query: Query<(&ProgressBar, &Children), Changed<ProgressBar>>,
+    mut fill_query: Query<&mut Node, With<ProgressBarFill>>,
+) {
+    for (bar, children) in &bar_query {
+        let mut fill_nodes = fill_query.iter_many_mut(children);
+        while let Some(mut node) = fill_nodes.fetch_next() {
+            node.width = percent(bar.0.clamp(0.0, 1.0) * 100.0);
+        }
+    }
+}
+
+/// A row of small pips, e.g. for ammo. The number of pips follows its [`Pips`].
+pub fn pips() -> impl Bundle {
+    (
+        Name::new("Pips"),
+        Node {
+            column_gap: px(4),
+            ..default()
+        },
+        Pips::default(),
+    )
+}
+
+/// How many pips a [`pips`] row shows, and how many of them are filled.
+#[derive(Component, Reflect, Default, PartialEq)]
+#[reflect(Component)]
+pub struct Pips {
+    pub filled: usize,
+    pub total: usize,
+}
+
+fn pip(filled: bool) -> impl Bundle {
+    (
+        Name::new("Pip"),
+        Node {
+            width: px(8),
+            height: px(16),
+            ..default()
+        },
+        BackgroundColor(pip_color(filled)),
+    )
+}
+
+fn pip_color(filled: bool) -> Color {
+    if filled { PIP_FILLED } else { PIP_EMPTY }
+}
+
+fn update_pips(
+    mut commands: Commands,
+    pips_query: Query<(Entity, &Pips, Option<&Children>), Changed<Pips>>,
+    mut color_query: Query<&mut BackgroundColor>,
+) {
+    for (entity, pips, children) in &pips_query {
+        let children = children.map_or(&[][..], |children| &children[..]);
+        if children.len() != pips.total {
+            let pips = (0..pips.total)
+                .map(|i| pip(i < pips.filled))
+                .collect::<Vec<_>>();
+            commands
+                .entity(entity)
+                .despawn_children()
+                .with_children(|parent| {
+                    for pip in pips {
+                        parent.spawn(pip);
+                    }
+                });
+            continue;
+        }
+        for (i, &child) in children.iter().enumerate() {
+            if let Ok(mut color) = color_query.get_mut(child) {
+                color.0 = pip_color(i < pips.filled);
+            }
+        }
+    }
+}
+
+/// How many segments a [`radial_progress`] indicator is made of.
+const RADIAL_SEGMENTS: usize = 12;
+
+/// A ring of segments that light up clockwise according to its [`RadialProgress`].
+pub fn radial_progress(radius: f32) -> impl Bundle {
+    (
+        Name::new("Radial Progress"),
+        Node {
+            width: px(radius * 2.0),
+            height: px(radius * 2.0),
+            ..default()
+        },
+        RadialProgress(0.0),
+        Children::spawn(SpawnIter((0..RADIAL_SEGMENTS).map(move |i| {
+            let angle = i as f32 / RADIAL_SEGMENTS as f32 * TAU;
+            (
+                Name::new("Radial Segment"),
+                Node {
+                    position_type: PositionType::Absolute,
+                    left: px(radius + angle.sin() * radius - 3.0),
+                    top: px(radius - angle.cos() * radius - 3.0),
+                    width: px(6),
+                    height: px(6),
+                    border_radius: BorderRadius::MAX,
+                    ..default()
+                },
+                BackgroundColor(PIP_EMPTY),
+            )
+        }))),
+    )
+}
+
+/// How far along a [`radial_progress`] indicator is, from 0.0 to 1.0.
+#[derive(Component, Reflect, PartialEq)]
+#[reflect(Component)]
+pub struct RadialProgress(pub f32);
+
+fn update_radial_progress(
+    radial_query: Query<(&RadialProgress, &Children), Changed<RadialProgress>>,
+    mut color_query: Query<&mut BackgroundColor>,
+) {
+    for (progress, children) in &radial_query {
+        let lit = (progress.0.clamp(0.0, 1.0) * RADIAL_SEGMENTS as f32) as usize;
+        for (i, child) in children.iter().enumerate() {
+            if let Ok(mut color) = color_query.get_mut(child) {
+                color.0 = pip_color(i < lit);
+            }
+        }
+    }
+}
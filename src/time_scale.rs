@@ -0,0 +1,76 @@
+//! Scaling how fast gameplay time passes, for slow motion and hit-stop.
+//!
+//! The simulation runs in [`FixedUpdate`], whose clock is fed by
+//! [`Time<Virtual>`]. Scaling virtual time slows movement, weapons, enemies and
+//! physics down by the same amount however many frames are rendered. Neither
+//! changes what happens on each tick, only when ticks happen. Replays record the
+//! input of every tick rather than every frame, so they stay in sync however
+//! time was scaled while recording or watching.
+
+use std::time::Duration;
+
+use bevy::{prelude::*, time::TimeSystems};
+
+use crate::screens::Screen;
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<TimeScale>();
+    app.add_systems(First, apply_time_scale.after(TimeSystems));
+    app.add_systems(OnExit(Screen::Gameplay), reset_time_scale);
+}
+
+/// How fast gameplay time passes relative to real time.
+#[derive(Resource, Debug)]
+pub struct TimeScale {
+    /// The speed outside of hit-stop, e.g. 0.5 for half speed.
+    pub slow_motion: f32,
+    /// How fast a replay is watched, on top of `slow_motion`. Replays store
+    /// input per tick, so this only changes how many ticks run per frame.
+    pub playback: f32,
+    /// Real time left until gameplay resumes after a hit-stop.
+    hit_stop: Duration,
+}
+
+impl Default for TimeScale {
+    fn default() -> Self {
+        Self {
+            slow_motion: 1.0,
+            playback: 1.0,
+            hit_stop: Duration::ZERO,
+        }
+    }
+}
+
+impl TimeScale {
+    /// Freeze gameplay for `duration` of real time, to give a hit more weight.
+    pub fn hit_stop(&mut self, duration: Duration) {
+        self.hit_stop = self.hit_stop.max(duration);
+    }
+
+    /// The current speed of gameplay time.
+    pub fn speed(&self) -> f32 {
+        if self.hit_stop.is_zero() {
+            self.slow_motion * self.playback
+        } else {
+            0.0
+        }
+    }
+}
+
+/// Count down hit-stop and apply the speed from the next frame on, since virtual
+/// time for this frame has already been advanced.
+fn apply_time_scale(
+    real_time: Res<Time<Real>>,
+    mut time_scale: ResMut<TimeScale>,
+    mut virtual_time: ResMut<Time<Virtual>>,
+) {
+    time_scale.hit_stop = time_scale.hit_stop.saturating_sub(real_time.delta());
+    let speed = time_scale.speed();
+    if virtual_time.relative_speed() != speed {
+        virtual_time.set_relative_speed(speed);
+    }
+}
+
+fn reset_time_scale(mut time_scale: ResMut<TimeScale>) {
+    *time_scale = TimeScale::default();
+}
@@ -0,0 +1,101 @@
+//! Gameplay tests, run headlessly with [`Simulation`].
+
+use bevy::prelude::*;
+use bevyjam7::{
+    gameplay::{enemy::Enemy, gun::Gun, movement::WRAP_AREA, player::Health, stats::RunStats},
+    headless::Simulation,
+    input::Action,
+    screens::Screen,
+};
+
+fn gun(simulation: &mut Simulation) -> &Gun {
+    let mut guns = simulation.world_mut().query::<&Gun>();
+    guns.single(simulation.world()).unwrap()
+}
+
+#[test]
+fn run_starts_with_a_player() {
+    let mut simulation = Simulation::new(0);
+    assert_eq!(simulation.screen(), Screen::Gameplay);
+    assert!(simulation.player().is_some());
+}
+
+#[test]
+fn bullet_damages_enemy() {
+    let mut simulation = Simulation::new(0);
+    let enemy = simulation.spawn_enemy(Vec2::new(200.0, 0.0));
+    simulation.step(1);
+    let full_health = simulation.world().get::<Enemy>(enemy).unwrap().health();
+
+    simulation.aim(Some(Vec2::X));
+    simulation.press(Action::Fire);
+    simulation.step(1);
+    simulation.release(Action::Fire);
+    simulation.step_seconds(1.0);
+
+    // With this seed the hit isn't critical, so it deals a bullet's base damage.
+    let health = simulation.world().get::<Enemy>(enemy).map(Enemy::health);
+    assert_eq!(health, Some(full_health - 10.0));
+    assert_eq!(simulation.world().resource::<RunStats>().shots_hit, 1);
+}
+
+#[test]
+fn reload_refills_ammo_after_two_seconds() {
+    let mut simulation = Simulation::new(0);
+    let max_ammo = gun(&mut simulation).max_ammo();
+
+    simulation.press(Action::Fire);
+    simulation.step(1);
+    simulation.release(Action::Fire);
+    assert_eq!(gun(&mut simulation).ammo(), max_ammo - 1);
+
+    // Wait for the shot to finish before reloading.
+    simulation.step_seconds(0.5);
+    simulation.press(Action::Reload);
+    simulation.step(1);
+    simulation.release(Action::Reload);
+    assert!(gun(&mut simulation).reload_progress().is_some());
+
+    simulation.step_seconds(1.9);
+    assert_eq!(gun(&mut simulation).ammo(), max_ammo - 1);
+    simulation.step_seconds(0.2);
+    assert_eq!(gun(&mut simulation).ammo(), max_ammo);
+}
+
+#[test]
+fn enemy_contact_hurts_player() {
+    let mut simulation = Simulation::new(0);
+    simulation.spawn_enemy(Vec2::new(100.0, 0.0));
+    simulation.step_seconds(2.0);
+
+    let player = simulation.player().unwrap();
+    let health = simulation.world().get::<Health>(player).unwrap();
+    assert!(health.current < health.max);
+}
+
+#[test]
+fn player_wraps_around_the_arena() {
+    let mut simulation = Simulation::new(0);
+    let player = simulation.player().unwrap();
+    let edge = WRAP_AREA.x / 2.0 - 1.0;
+    simulation
+        .world_mut()
+        .get_mut::<Transform>(player)
+        .unwrap()
+        .translation
+        .x = edge;
+
+    simulation.press(Action::MoveRight);
+    simulation.step_seconds(0.5);
+
+    let x = simulation
+        .world()
+        .get::<Transform>(player)
+        .unwrap()
+        .translation
+        .x;
+    assert!(
+        x < 0.0,
+        "the player should have wrapped to the left edge, but is at {x}"
+    );
+}
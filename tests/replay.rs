@@ -0,0 +1,39 @@
+//! Replay tests, run headlessly with [`Simulation`].
+//!
+//! Replays are saved to storage that every test in the process shares, so
+//! these get a process of their own.
+
+use bevy::prelude::*;
+use bevyjam7::{
+    gameplay::stats::RunStats, headless::Simulation, input::Action, time_scale::TimeScale,
+};
+
+#[test]
+fn replay_with_hit_stop_stays_in_sync() {
+    // With this seed the first hit is critical, and a critical kill stops time.
+    let mut simulation = Simulation::new(1);
+
+    // Move away from where enemies spawn, then spawn one and shoot it.
+    simulation.press(Action::MoveLeft);
+    simulation.step_seconds(1.0);
+    simulation.release(Action::MoveLeft);
+    simulation.press(Action::SpawnEnemy);
+    simulation.step(1);
+    simulation.release(Action::SpawnEnemy);
+    simulation.aim(Some(Vec2::X));
+    simulation.press(Action::Fire);
+    simulation.step(1);
+    simulation.release(Action::Fire);
+
+    let mut hit_stopped = false;
+    for _ in 0..60 {
+        simulation.step(1);
+        hit_stopped |= simulation.world().resource::<TimeScale>().speed() == 0.0;
+    }
+    assert!(hit_stopped);
+    assert_eq!(simulation.world().resource::<RunStats>().total_kills(), 1);
+    simulation.step_seconds(0.5);
+
+    simulation.quit_to_title();
+    assert_eq!(simulation.watch_last_replay(), None);
+}